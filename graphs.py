@@ -133,6 +133,32 @@ def violin(df, ycol, out_dir, pass_="", limit=None):
     else:
         plt.savefig(f"{out_dir}/violin_{ycol}_{pass_}.png")
 
+# Relate the speedup of an executor over the sequential one to the parallelism
+# available in each benchmark, measured as the average number of blocks per
+# round of the parallel executor.
+def speedup_vs_width(df, out_dir, executor="parallel"):
+    df = df.groupby(["executor", "pass", "name"]).mean(numeric_only=True).reset_index()
+
+    width = df[df["executor"] == "parallel"].set_index(["pass", "name"])
+    width = width["pushes"] / width["rounds"]
+    sequential_times = df[df["executor"] == "sequential"].set_index(["pass", "name"])["runtime"]
+
+    df = df[df["executor"] == executor].set_index(["pass", "name"])
+    df["speedup"] = sequential_times / df["runtime"]
+    df["width"] = width
+    df = df.reset_index()
+
+    plt.figure(figsize=(10, 6))
+    sns.scatterplot(x="width", y="speedup", hue="pass", data=df)
+    plt.axhline(1, color="gray", linestyle="--")
+    plt.title(f"Speedup of {executor} by Average Round Width")
+    plt.xlabel("Average blocks per parallel round")
+    plt.ylabel("Speedup over sequential")
+    plt.xscale("log")
+    plt.legend(title="Pass")
+    plt.tight_layout()
+    plt.savefig(f"{out_dir}/speedup_vs_width_{executor}.png")
+
 if __name__ == "__main__":
     parser = argparse.ArgumentParser(description="Plot graphs from performance data.")
     parser.add_argument("--data", type=str, help="Path to the CSV file", default="perf.csv")
@@ -157,4 +183,8 @@ if __name__ == "__main__":
     violin(df, "runtime", args.out_dir, "AvailableExpr")
     violin(df, "loadtime", args.out_dir)
     violin(df, "runtime", args.out_dir, limit=3)
-    violin(df, "loadtime", args.out_dir, limit=3)
\ No newline at end of file
+    violin(df, "loadtime", args.out_dir, limit=3)
+
+    if "rounds" in df.columns:
+        speedup_vs_width(df, args.out_dir, "parallel")
+        speedup_vs_width(df, args.out_dir, "async-parallel")
\ No newline at end of file
@@ -0,0 +1,161 @@
+use crate::schedule::{SharedStats, TaskState};
+use arc_swap::ArcSwap;
+use bril_utils::{CFG, Dataflow};
+use itertools::Itertools;
+use std::sync::Arc;
+use utils::{BlockTransfer, DataflowExecutor, DataflowSpec, DataflowStats};
+
+#[derive(Default)]
+/// Asynchronous parallel worklist algorithm. Every block is a rayon task that
+/// reschedules its successors as soon as its out value changes, so there is no
+/// barrier between rounds.
+pub struct AsyncParallelExecutor;
+
+/// State shared between all tasks working on a single CFG
+struct Worklist<'a, Pass: DataflowSpec> {
+    pass: &'a Pass,
+    cfg: &'a CFG,
+    transfer: BlockTransfer<'a, Pass>,
+    /// Values are swapped atomically, so neither readers nor the writer ever
+    /// take a lock
+    in_vals: Vec<ArcSwap<Pass::Val>>,
+    out_vals: Vec<ArcSwap<Pass::Val>>,
+    states: Vec<TaskState>,
+    stats: SharedStats,
+}
+
+impl<'a, Pass> Worklist<'a, Pass>
+where
+    Pass: DataflowSpec + Send + Sync,
+{
+    fn new(pass: &'a Pass, cfg: &'a CFG) -> Self {
+        let n = cfg.len();
+        let init = pass.init(cfg.func());
+
+        Self {
+            pass,
+            cfg,
+            transfer: BlockTransfer::new(pass, cfg),
+            in_vals: (0..n)
+                .map(|_| ArcSwap::from_pointee(init.clone()))
+                .collect(),
+            out_vals: (0..n)
+                .map(|_| ArcSwap::from_pointee(init.clone()))
+                .collect(),
+            states: (0..n).map(|_| TaskState::default()).collect(),
+            stats: SharedStats::default(),
+        }
+    }
+
+    /// Get a snapshot of the current out value of a block
+    fn out_val(&self, i: usize) -> Arc<Pass::Val> {
+        self.out_vals[i].load_full()
+    }
+
+    /// Spawn a task for block `i` unless one is already pending
+    fn schedule<'s>(&'s self, scope: &rayon::Scope<'s>, i: usize) {
+        if self.states[i].schedule() {
+            self.stats.push(1);
+            scope.spawn(move |scope| self.process(scope, i));
+        }
+    }
+
+    /// Process block `i` until its out value is stable with respect to the
+    /// inputs it has seen
+    fn process<'s>(&'s self, scope: &rayon::Scope<'s>, i: usize) {
+        self.states[i].start();
+
+        loop {
+            let in_val = if self.cfg.func().get(i).is_entry() {
+                self.pass.entry(self.cfg.func())
+            } else {
+                let preds = self.cfg.preds(i).iter().copied().collect_vec();
+                let inputs = preds
+                    .iter()
+                    .map(|&j| {
+                        let val = self.out_val(j).as_ref().clone();
+                        self.pass.edge_transfer(self.cfg.func(), j, i, val)
+                    })
+                    .collect_vec();
+                self.stats.meet();
+                self.pass.meet_edges(self.cfg.func(), i, &preds, &inputs)
+            };
+
+            let old_vals = self.out_val(i);
+            let new_vals = self.transfer.apply(i, &in_val, &old_vals);
+            self.stats.transfer();
+            self.in_vals[i].store(Arc::new(in_val));
+
+            // Only the task owning block i ever writes its out value
+            if *old_vals != new_vals {
+                log::trace!("New values for block {}: {:?}", i, new_vals);
+                self.out_vals[i].store(Arc::new(new_vals));
+                for j in self.cfg.succs(i) {
+                    self.schedule(scope, j);
+                }
+            }
+
+            if self.states[i].finish() {
+                break;
+            }
+        }
+
+        self.stats.pop();
+    }
+
+    fn into_vals(self) -> (Vec<Pass::Val>, Vec<Pass::Val>, DataflowStats) {
+        let n = self.cfg.len();
+        let in_vals = self
+            .in_vals
+            .into_iter()
+            .map(|v| Arc::unwrap_or_clone(v.into_inner()))
+            .collect();
+        let out_vals = self
+            .out_vals
+            .into_iter()
+            .map(|v| Arc::unwrap_or_clone(v.into_inner()))
+            .collect();
+        (in_vals, out_vals, self.stats.into_stats(n))
+    }
+}
+
+impl<Pass> DataflowExecutor<Pass> for AsyncParallelExecutor
+where
+    Pass: DataflowSpec + Send + Sync,
+{
+    fn cfg(&self, pass: &Pass, cfg: CFG) -> (Dataflow<Pass::Val>, DataflowStats) {
+        log::debug!("Function {}", cfg.name());
+        let cfg = if cfg.reversed() != pass.reversed() {
+            cfg.reverse()
+        } else {
+            cfg
+        };
+
+        let worklist = Worklist::new(pass, &cfg);
+        rayon::scope(|scope| {
+            for i in 0..cfg.len() {
+                worklist.schedule(scope, i);
+            }
+        });
+        let (in_vals, out_vals, stats) = worklist.into_vals();
+
+        // The exit value can be computed by meeting all the out values of exit block(s)
+        let exit_val = cfg
+            .exits()
+            .into_iter()
+            .map(|i| out_vals[i].clone())
+            .collect_vec();
+        let exit_val = pass.meet(&exit_val);
+        let exit_val = pass.finish(cfg.func(), exit_val);
+
+        (
+            Dataflow {
+                cfg,
+                in_vals,
+                out_vals,
+                exit_val,
+            },
+            stats,
+        )
+    }
+}
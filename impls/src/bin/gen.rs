@@ -0,0 +1,42 @@
+use argh::FromArgs;
+use impls::{GenConfig, generate};
+
+#[derive(FromArgs)]
+/// Print a random but well-formed Bril program in text form
+struct Args {
+    /// seed of the random number generator
+    #[argh(option, short = 's', default = "0")]
+    seed: u64,
+    /// number of functions, including main
+    #[argh(option, short = 'f', default = "1")]
+    funcs: usize,
+    /// number of blocks of each function, not counting the entry block
+    #[argh(option, short = 'b', default = "50")]
+    blocks: usize,
+    /// maximum number of successors of a conditional
+    #[argh(option, short = 'k', default = "2")]
+    branching: usize,
+    /// maximum nesting depth of loops
+    #[argh(option, short = 'd', default = "2")]
+    loop_depth: usize,
+    /// number of integer variables of each function
+    #[argh(option, short = 'v', default = "8")]
+    vars: usize,
+    /// number of random instructions at the start of each block
+    #[argh(option, short = 'i', default = "4")]
+    instrs: usize,
+}
+
+fn main() {
+    let args: Args = argh::from_env();
+    let config = GenConfig {
+        funcs: args.funcs,
+        blocks: args.blocks,
+        branching: args.branching,
+        loop_depth: args.loop_depth,
+        vars: args.vars,
+        instrs: args.instrs,
+    };
+
+    print!("{}", generate(&config, args.seed));
+}
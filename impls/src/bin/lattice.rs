@@ -0,0 +1,240 @@
+use argh::FromArgs;
+use bril_utils::BasicBlock;
+use impls::{Pass, SequentialExecutor};
+use passes::{
+    AvailableExpr, AvailableExprBv, ConstProp, Dominators, IntervalAnalysis, LiveVars, LiveVarsBv,
+    Parity, ReachingDefs, ReachingDefsBv, Sccp, Set, Sign, VeryBusyExprs,
+};
+use rand::{Rng, SeedableRng, rngs::StdRng, seq::SliceRandom};
+use simple_logger::SimpleLogger;
+use strum::IntoEnumIterator;
+use utils::{DataflowExecutor, DataflowSpec, Product};
+
+#[derive(FromArgs)]
+/// Check the lattice laws of every pass on random values built from the
+/// results of each benchmark in a directory
+struct Args {
+    /// the log level
+    #[argh(option, short = 'l', default = "log::LevelFilter::Info")]
+    log: log::LevelFilter,
+    /// directory of benchmarks
+    #[argh(option, short = 'd', default = "String::from(\"core/\")")]
+    dir: String,
+    /// number of random samples per function
+    #[argh(option, short = 'n', default = "100")]
+    samples: usize,
+    /// seed of the random number generator
+    #[argh(option, short = 's', default = "0")]
+    seed: u64,
+}
+
+/// Build a pool of random values of a pass for a function, starting from its
+/// solution and the initial values, and adding meets of random values and
+/// transfers of random values through random blocks
+fn sample_pool<P: DataflowSpec>(
+    pass: &P,
+    blocks: &[&BasicBlock],
+    mut pool: Vec<P::Val>,
+    rng: &mut StdRng,
+    samples: usize,
+) -> Vec<P::Val> {
+    for _ in 0..samples {
+        let val = if rng.gen_bool(0.5) {
+            let count = rng.gen_range(1..=3);
+            let vals = pool
+                .choose_multiple(rng, count)
+                .cloned()
+                .collect::<Vec<_>>();
+            pass.meet(&vals)
+        } else {
+            let val = pool.choose(rng).unwrap();
+            pass.transfer(blocks.choose(rng).unwrap(), val)
+        };
+        pool.push(val);
+    }
+    pool
+}
+
+/// Check that `meet` is commutative, associative and idempotent, that the meet
+/// of nothing is the identity, and that `transfer` is monotone
+fn check_laws<P: DataflowSpec + Send + Sync>(
+    pass: &P,
+    path: &std::path::Path,
+    rng: &mut StdRng,
+    samples: usize,
+) {
+    let input = std::fs::File::open(path).unwrap();
+    let (_, result) = SequentialExecutor.run(pass, input, false);
+
+    for d in result {
+        let func = d.cfg.func();
+        let blocks = (0..d.cfg.len()).map(|i| func.get(i)).collect::<Vec<_>>();
+        let top = pass.meet(&[]);
+
+        let mut pool = vec![pass.init(func), pass.entry(func), top.clone()];
+        pool.extend(d.in_vals.iter().cloned());
+        pool.extend(d.out_vals.iter().cloned());
+        let pool = sample_pool(pass, &blocks, pool, rng, samples);
+
+        // Only passes that start from the top of the lattice need the initial
+        // value to be the identity of the meet
+        let init = pass.init(func);
+        let init_is_top = init == top;
+
+        for _ in 0..samples {
+            let a = pool.choose(rng).unwrap();
+            let b = pool.choose(rng).unwrap();
+            let c = pool.choose(rng).unwrap();
+            let meet =
+                |vals: &[&P::Val]| pass.meet(&vals.iter().map(|&v| v.clone()).collect::<Vec<_>>());
+
+            let fail = |law: &str| {
+                log::error!("a: {:?}", a);
+                log::error!("b: {:?}", b);
+                log::error!("c: {:?}", c);
+                panic!(
+                    "{} does not hold in @{} of {}",
+                    law,
+                    func.name,
+                    path.display()
+                );
+            };
+
+            let ab = meet(&[a, b]);
+            if ab != meet(&[b, a]) {
+                fail("Commutativity");
+            }
+            let bc = meet(&[b, c]);
+            if meet(&[&ab, c]) != meet(&[a, &bc]) {
+                fail("Associativity");
+            }
+            if meet(&[a, a]) != *a {
+                fail("Idempotence");
+            }
+            if meet(&[a, b, c]) != meet(&[&ab, c]) {
+                fail("Meet of many values");
+            }
+            if meet(&[a, &top]) != *a || meet(&[a]) != *a {
+                fail("Identity of the empty meet");
+            }
+            if init_is_top && meet(&[a, &init]) != *a {
+                fail("Identity of the initial value");
+            }
+
+            // ab is below a, so its transfer must be below the transfer of a
+            let block = blocks.choose(rng).unwrap();
+            let below = pass.transfer(block, &ab);
+            if meet(&[&below, &pass.transfer(block, a)]) != below {
+                log::error!("Block: {:?}", block.idx);
+                fail("Monotonicity of the transfer function");
+            }
+        }
+    }
+}
+
+/// A program that defines `x` on only one path to the block that reads it
+const UNBOUND: &str = r#"
+@main(c: bool) {
+  one: int = const 1;
+  br c .then .join;
+.then:
+  x: int = const 5;
+  jmp .join;
+.join:
+  y: int = add x one;
+  z: int = id x;
+  print y z;
+}
+"#;
+
+/// Check that `transfer` is monotone on every pair of values of the solution
+/// of `UNBOUND`, some of which bind `x` and some of which do not
+fn check_unbound<P: DataflowSpec + Send + Sync>(pass: &P) {
+    let (_, result) = SequentialExecutor.run(pass, UNBOUND.as_bytes(), false);
+
+    for d in result {
+        let func = d.cfg.func();
+        let mut vals = vec![pass.init(func), pass.entry(func)];
+        vals.extend(d.in_vals.iter().cloned());
+        vals.extend(d.out_vals.iter().cloned());
+
+        for a in vals.iter() {
+            for b in vals.iter() {
+                let ab = pass.meet(&[a.clone(), b.clone()]);
+                for i in 0..d.cfg.len() {
+                    let below = pass.transfer(func.get(i), &ab);
+                    let above = pass.transfer(func.get(i), a);
+                    assert!(
+                        pass.meet(&[below.clone(), above]) == below,
+                        "Transfer of block {} is not monotone on {:?} and {:?}",
+                        i,
+                        a,
+                        b
+                    );
+                }
+            }
+        }
+    }
+}
+
+/// Check that the full set is the top of a lattice of sets
+fn check_full<P: DataflowSpec<Val = Set>>(pass: &P) {
+    assert!(
+        pass.meet(&[]) == Set::Full,
+        "The full set is not the identity of the meet"
+    );
+}
+
+fn main() {
+    let args: Args = argh::from_env();
+    let dir = std::fs::read_dir(args.dir).unwrap();
+
+    SimpleLogger::new()
+        .with_colors(true)
+        .with_level(args.log)
+        .without_timestamps()
+        .init()
+        .unwrap();
+
+    check_full(&AvailableExpr);
+    check_full(&VeryBusyExprs);
+    check_unbound(&ConstProp);
+    check_unbound(&Sccp);
+    check_unbound(&Sign);
+    check_unbound(&Parity);
+    check_unbound(&IntervalAnalysis::default());
+
+    let mut rng = StdRng::seed_from_u64(args.seed);
+    let n = args.samples;
+
+    for entry in dir {
+        let entry = entry.unwrap();
+        if entry.path().extension().unwrap() == "bril" {
+            log::info!("Lattice laws {}", entry.path().display());
+            let path = entry.path();
+
+            for pass in Pass::iter() {
+                match pass {
+                    Pass::ReachingDefinitions => check_laws(&ReachingDefs, &path, &mut rng, n),
+                    Pass::LiveVariables => check_laws(&LiveVars, &path, &mut rng, n),
+                    Pass::ConstProp => check_laws(&ConstProp, &path, &mut rng, n),
+                    Pass::AvailableExpr => check_laws(&AvailableExpr, &path, &mut rng, n),
+                    Pass::ReachingDefinitionsBv => {
+                        check_laws(&ReachingDefsBv::default(), &path, &mut rng, n)
+                    }
+                    Pass::LiveVariablesBv => check_laws(&LiveVarsBv::default(), &path, &mut rng, n),
+                    Pass::AvailableExprBv => {
+                        check_laws(&AvailableExprBv::default(), &path, &mut rng, n)
+                    }
+                    Pass::Dominators => check_laws(&Dominators, &path, &mut rng, n),
+                    Pass::VeryBusyExprs => check_laws(&VeryBusyExprs, &path, &mut rng, n),
+                    Pass::Intervals => check_laws(&IntervalAnalysis::default(), &path, &mut rng, n),
+                    Pass::Sign => check_laws(&Sign, &path, &mut rng, n),
+                    Pass::Parity => check_laws(&Parity, &path, &mut rng, n),
+                    Pass::SignParity => check_laws(&Product::new(Sign, Parity), &path, &mut rng, n),
+                    Pass::Sccp => check_laws(&Sccp, &path, &mut rng, n),
+                };
+            }
+        }
+    }
+}
@@ -1,6 +1,7 @@
 use argh::FromArgs;
-use impls::{Executor, Pass};
+use impls::{Check, Executor, Format, Pass, Transform};
 use simple_logger::SimpleLogger;
+use utils::DataflowStats;
 
 #[derive(FromArgs)]
 /// Run a pass with a specified executor
@@ -13,10 +14,26 @@ pub struct Args {
     algorithm: Executor,
     /// the pass to run
     #[argh(option, short = 'p')]
-    pass: Pass,
-    /// flag to output raw perf data
+    pass: Option<Pass>,
+    /// a check to run instead of a pass, printing one diagnostic per line:
+    /// uninit or unreachable
+    #[argh(option, short = 'c')]
+    check: Option<Check>,
+    /// a transformation to run instead of a pass, printing the rewritten
+    /// program as Bril JSON: dce, cse or const-fold
+    #[argh(option, short = 't')]
+    transform: Option<Transform>,
+    /// flag to output raw perf data: load time, runtime, then the work counters
+    /// summed over all functions, one number per line
     #[argh(switch, short = 'r')]
     raw: bool,
+    /// the output format of the results: debug, json, annotated or dot
+    #[argh(option, short = 'f', default = "Format::Debug")]
+    format: Format,
+    /// with the dot format, highlight the blocks processed in this round
+    /// (0-indexed) by a round-based executor such as parallel
+    #[argh(option)]
+    round: Option<usize>,
 }
 
 fn main() {
@@ -29,15 +46,46 @@ fn main() {
         .init()
         .unwrap();
 
-    let (timing, result) = args.pass.execute(&args.algorithm, std::io::stdin().lock());
+    let mut format = args.format;
+    if let Format::Dot(round) = &mut format {
+        *round = args.round;
+    } else if args.round.is_some() {
+        log::warn!("--round only applies to the dot format");
+    }
+
+    let input = std::io::stdin().lock();
+    let (timing, result) = match (args.pass, args.check, args.transform) {
+        (Some(pass), None, None) => pass.execute(&args.algorithm, input, format),
+        (None, Some(check), None) => check.execute(&args.algorithm, input),
+        (None, None, Some(transform)) => {
+            // The rewritten program is the only output
+            println!("{}", transform.execute(&args.algorithm, input));
+            return;
+        }
+        _ => {
+            log::error!("Exactly one of --pass, --check and --transform must be given");
+            std::process::exit(1);
+        }
+    };
+    let stats: DataflowStats = timing.stats.iter().cloned().sum();
 
     if args.raw {
         println!("{}", timing.loadtime.as_nanos());
         println!("{}", timing.runtime.as_nanos());
+        println!("{}", stats.transfers);
+        println!("{}", stats.meets);
+        println!("{}", stats.pushes);
+        println!("{}", stats.rounds);
+        println!("{}", stats.max_width);
+        println!("{}", stats.reprocessed);
+    } else if args.format != Format::Debug {
+        // Keep stdout parseable
+        println!("{}", result);
     } else {
         println!("{}", result);
 
         println!("Load time: {:?}", timing.loadtime);
         println!("Runtime: {:?}", timing.runtime);
+        println!("{:?}", stats);
     }
 }
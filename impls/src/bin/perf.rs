@@ -1,10 +1,9 @@
 use argh::FromArgs;
-use impls::{Executor, Pass};
+use impls::{Executor, GenConfig, Pass, generate};
 use itertools::Itertools;
 use serde::Serialize;
 use simple_logger::SimpleLogger;
-use std::process::Command;
-use strum::IntoEnumIterator;
+use std::{fs::File, path::Path, process::Command};
 
 #[derive(FromArgs)]
 /// Generate performance statistics for all benchmarks in a directory
@@ -22,9 +21,36 @@ struct Args {
     /// number of iterations per benchmark
     #[argh(option, short = 'i', default = "10")]
     iterations: usize,
+    /// pass to benchmark. Can be given more than once; defaults to reaching
+    /// definitions, live variables and available expressions.
+    #[argh(option)]
+    passes: Vec<Pass>,
     /// only run the parallel-across-functions evaluation
     #[argh(switch, short = 'p')]
     par_func: bool,
+    /// benchmark a random program with this many blocks instead of the
+    /// benchmarks in the directory. Can be given more than once to sweep over
+    /// CFG sizes.
+    #[argh(option)]
+    sweep: Vec<usize>,
+    /// seed of the random programs of --sweep
+    #[argh(option, default = "0")]
+    seed: u64,
+    /// number of functions of the random programs of --sweep, including main
+    #[argh(option, default = "1")]
+    funcs: usize,
+    /// maximum number of successors of a conditional in --sweep
+    #[argh(option, default = "2")]
+    branching: usize,
+    /// maximum nesting depth of loops in --sweep
+    #[argh(option, default = "2")]
+    loop_depth: usize,
+    /// number of integer variables of each function in --sweep
+    #[argh(option, default = "8")]
+    vars: usize,
+    /// number of random instructions at the start of each block in --sweep
+    #[argh(option, default = "4")]
+    instrs: usize,
 }
 
 #[derive(Serialize)]
@@ -35,8 +61,96 @@ struct Record {
     iteration: usize,
     loadtime: u128,
     runtime: u128,
+    transfers: u128,
+    meets: u128,
+    pushes: u128,
+    rounds: u128,
+    max_width: u128,
+    reprocessed: u128,
 }
 
+/// Number of lines printed by `main -r`
+const RAW_FIELDS: usize = 8;
+
+/// Run a single pass - executor combination on a benchmark in a new process
+fn measure(
+    path: &Path,
+    name: &str,
+    pass: Pass,
+    executor: Executor,
+    iteration: usize,
+) -> Option<Record> {
+    // Dispatch a new process for each pass and executor to avoid cache
+    // pollution. The process is located in /target/release/main
+    let output = Command::new(MAIN_EXECUTABLE)
+        .stdin(std::fs::File::open(path).unwrap())
+        .arg("-r") // raw output
+        .arg("-a") // algorithm
+        .arg(executor.to_string())
+        .arg("-p") // pass
+        .arg(pass.to_string())
+        .output()
+        .unwrap();
+
+    let output = std::str::from_utf8(&output.stdout).unwrap();
+
+    // Output consists of the 2 times in nanoseconds followed by the work
+    // counters, separated by newlines
+    let fields: Vec<u128> = output
+        .lines()
+        .map(|line| line.parse::<u128>().unwrap())
+        .collect();
+
+    if fields.len() != RAW_FIELDS {
+        log::error!("Invalid output: {}", output);
+        return None;
+    }
+
+    Some(Record {
+        name: name.into(),
+        pass,
+        executor,
+        iteration,
+        loadtime: fields[0],
+        runtime: fields[1],
+        transfers: fields[2],
+        meets: fields[3],
+        pushes: fields[4],
+        rounds: fields[5],
+        max_width: fields[6],
+        reprocessed: fields[7],
+    })
+}
+
+/// Run every combination of the given passes and executors on a benchmark
+fn measure_all(
+    wtr: &mut csv::Writer<File>,
+    path: &Path,
+    name: &str,
+    passes: &[Pass],
+    iterations: usize,
+) {
+    for &pass in passes {
+        for executor in Executor::iter() {
+            for iter in 0..iterations {
+                if let Some(record) = measure(path, name, pass, executor, iter) {
+                    wtr.serialize(record).unwrap();
+                }
+            }
+        }
+    }
+}
+
+/// Passes benchmarked when --passes is not given
+const DEFAULT_PASSES: [Pass; 3] = [
+    Pass::ReachingDefinitions,
+    Pass::LiveVariables,
+    Pass::AvailableExpr,
+];
+
+/// Directory of the random programs of --sweep
+const SWEEP_DIR: &str = "./target/sweep";
+
 // Path to the main executable
 #[cfg(debug_assertions)]
 const MAIN_EXECUTABLE: &str = "./target/debug/main";
@@ -60,6 +174,12 @@ fn main() {
     #[cfg(debug_assertions)]
     log::warn!("Running performance benchmarks in debug mode. This may be very slow.");
 
+    let passes = if args.passes.is_empty() {
+        DEFAULT_PASSES.to_vec()
+    } else {
+        args.passes
+    };
+
     log::info!("Writing results to {}", args.output);
     let mut wtr = csv::Writer::from_path(args.output).unwrap();
 
@@ -76,43 +196,16 @@ fn main() {
                 );
             }
 
-            for pass in Pass::iter().filter(|pass| !matches!(pass, Pass::ConstProp)) {
+            for &pass in &passes {
                 for executor in Executor::iter().filter(|exec| {
                     *exec == Executor::Sequential || *exec == Executor::ParallelizedAcrossFunctions
                 }) {
                     for iter in 0..args.iterations {
-                        let output = Command::new(MAIN_EXECUTABLE)
-                            .stdin(std::fs::File::open(entry.path()).unwrap())
-                            .arg("-r") // raw output
-                            .arg("-a") // algorithm
-                            .arg(executor.to_string())
-                            .arg("-p") // pass
-                            .arg(pass.to_string())
-                            .output()
-                            .unwrap();
-
-                        let output = std::str::from_utf8(&output.stdout).unwrap();
-
-                        // Output consists of the 2 times in nanoseconds separated by newlines
-                        let times: Vec<u128> = output
-                            .lines()
-                            .map(|line| line.parse::<u128>().unwrap())
-                            .collect();
-
-                        if times.len() != 2 {
-                            log::error!("Invalid output: {}", output);
-                            continue;
+                        if let Some(record) =
+                            measure(&entry.path(), entry_name, pass, executor, iter)
+                        {
+                            wtr.serialize(record).unwrap();
                         }
-
-                        wtr.serialize(Record {
-                            name: entry_name.into(),
-                            pass,
-                            executor,
-                            iteration: iter,
-                            loadtime: times[0],
-                            runtime: times[1],
-                        })
-                        .unwrap();
                     }
                 }
             }
@@ -120,6 +213,32 @@ fn main() {
         return;
     }
 
+    if !args.sweep.is_empty() {
+        std::fs::create_dir_all(SWEEP_DIR).unwrap();
+        for blocks in args.sweep {
+            let name = format!("gen{}", blocks);
+            let path = Path::new(SWEEP_DIR).join(format!("{}.bril", name));
+            let config = GenConfig {
+                funcs: args.funcs,
+                blocks,
+                branching: args.branching,
+                loop_depth: args.loop_depth,
+                vars: args.vars,
+                instrs: args.instrs,
+            };
+            std::fs::write(&path, generate(&config, args.seed)).unwrap();
+
+            log::info!(
+                "Running ({}x) benchmarks for {} blocks",
+                args.iterations,
+                blocks
+            );
+            measure_all(&mut wtr, &path, &name, &passes, args.iterations);
+        }
+        wtr.flush().unwrap();
+        return;
+    }
+
     for entry in dir {
         let entry = entry.unwrap();
         let entry_name = entry.file_name().clone();
@@ -130,47 +249,13 @@ fn main() {
                 args.iterations,
                 entry.path().display(),
             );
-            for pass in Pass::iter().filter(|pass| !matches!(pass, Pass::ConstProp)) {
-                for executor in Executor::iter() {
-                    for iter in 0..args.iterations {
-                        // Dispatch a new process for each pass and executor to avoid cache
-                        // pollution. The process is located in /target/release/main
-
-                        let output = Command::new(MAIN_EXECUTABLE)
-                            .stdin(std::fs::File::open(entry.path()).unwrap())
-                            .arg("-r") // raw output
-                            .arg("-a") // algorithm
-                            .arg(executor.to_string())
-                            .arg("-p") // pass
-                            .arg(pass.to_string())
-                            .output()
-                            .unwrap();
-
-                        let output = std::str::from_utf8(&output.stdout).unwrap();
-
-                        // Output consists of the 2 times in nanoseconds separated by newlines
-                        let times: Vec<u128> = output
-                            .lines()
-                            .map(|line| line.parse::<u128>().unwrap())
-                            .collect();
-
-                        if times.len() != 2 {
-                            log::error!("Invalid output: {}", output);
-                            continue;
-                        }
-
-                        wtr.serialize(Record {
-                            name: entry_name.into(),
-                            pass,
-                            executor,
-                            iteration: iter,
-                            loadtime: times[0],
-                            runtime: times[1],
-                        })
-                        .unwrap();
-                    }
-                }
-            }
+            measure_all(
+                &mut wtr,
+                &entry.path(),
+                entry_name,
+                &passes,
+                args.iterations,
+            );
         }
     }
 
@@ -1,9 +1,18 @@
 use argh::FromArgs;
-use impls::{Executor, Pass, execute_pass};
-use passes::{AvailableExpr, ConstProp, LiveVars, ReachingDefs};
+use bril_utils::bril_rs::{Code, Instruction, Literal, Program, ValueOps};
+use impls::SequentialExecutor;
+use impls::{Executor, GenConfig, Pass, Transform, check_soundness, execute_pass, generate};
+use passes::{
+    AvailableExpr, AvailableExprBv, BlockSet, ConstProp, DominatorTree, Dominators, Interval,
+    IntervalAnalysis, LiveVars, LiveVarsBv, Parity, ReachingDefinition, ReachingDefs,
+    ReachingDefsBv, Sccp, SccpVal, Sign, UninitDefs, UninitUse, Value, VeryBusyExprs,
+};
 use simple_logger::SimpleLogger;
 use strum::IntoEnumIterator;
-use utils::DataflowExecutor;
+use utils::{
+    DataflowExecutor, DataflowSpec, InstrDataflow, Interpreter, Product, bench_args, block_label,
+    load_program, loop_heads,
+};
 
 #[derive(FromArgs)]
 /// Generate performance statistics for all benchmarks in a directory
@@ -15,11 +24,20 @@ struct Args {
     /// directory of benchmarks
     #[argh(option, short = 'd', default = "String::from(\"core/\")")]
     dir: String,
+    /// only check that every transformation preserves the printed output of
+    /// each benchmark, using the built-in interpreter
+    #[argh(switch, short = 'i')]
+    interp: bool,
+    /// only run each benchmark with the built-in interpreter, checking that the
+    /// facts of live variables, reaching definitions and the constant and
+    /// integer passes hold at every block it goes through
+    #[argh(switch, short = 's')]
+    sound: bool,
 }
 
 /// Has to be done in a macro due to different types for different passes
 macro_rules! test {
-    ($pass: ident, $input: expr) => {{
+    ($pass: expr, $input: expr) => {{
         let mut expectation = None;
 
         for ref executor in Executor::iter() {
@@ -69,6 +87,627 @@ macro_rules! test {
     }};
 }
 
+/// Widened results depend on the order in which blocks are visited, so instead
+/// of comparing executors, check that every executor reaches a stable solution
+macro_rules! check_fixpoint {
+    ($pass: expr, $input: expr) => {{
+        let pass = $pass;
+
+        for ref executor in Executor::iter() {
+            let input = std::fs::File::open($input).unwrap();
+            let (_, result) = execute_pass!(pass, executor, input);
+
+            for d in result {
+                let heads = loop_heads(&d.cfg);
+                for i in 0..d.cfg.len() {
+                    let block = d.cfg.func().get(i);
+                    if !block.is_entry() {
+                        let preds: Vec<_> = d.cfg.preds(i).iter().copied().collect();
+                        let inputs: Vec<_> = preds
+                            .iter()
+                            .map(|&j| pass.edge_transfer(d.cfg.func(), j, i, d.out_vals[j].clone()))
+                            .collect();
+                        assert_eq!(
+                            pass.meet_edges(d.cfg.func(), i, &preds, &inputs),
+                            d.in_vals[i],
+                            "Executor {} left a stale in value for block {}",
+                            executor,
+                            i
+                        );
+                    }
+
+                    let new_vals = pass.transfer(block, &d.in_vals[i]);
+                    assert!(
+                        new_vals == d.out_vals[i]
+                            || (heads[i] && pass.widen(&d.out_vals[i], new_vals) == d.out_vals[i]),
+                        "Executor {} left an unstable out value for block {}",
+                        executor,
+                        i
+                    );
+                }
+            }
+        }
+    }};
+}
+
+/// Check that a product of two passes computes the same values as running
+/// each pass on its own
+macro_rules! check_product {
+    ($a: expr, $b: expr, $input: expr) => {{
+        let input = std::fs::File::open($input).unwrap();
+        let (_, product) = SequentialExecutor.run(&Product::new($a, $b), input, false);
+        let input = std::fs::File::open($input).unwrap();
+        let (_, a) = SequentialExecutor.run(&$a, input, false);
+        let input = std::fs::File::open($input).unwrap();
+        let (_, b) = SequentialExecutor.run(&$b, input, false);
+
+        for ((p, a), b) in product.iter().zip(a.iter()).zip(b.iter()) {
+            for i in 0..p.cfg.len() {
+                assert_eq!(
+                    p.in_vals[i].0, a.in_vals[i],
+                    "Product differs at block {}",
+                    i
+                );
+                assert_eq!(
+                    p.in_vals[i].1, b.in_vals[i],
+                    "Product differs at block {}",
+                    i
+                );
+                assert_eq!(
+                    p.out_vals[i].0, a.out_vals[i],
+                    "Product differs at block {}",
+                    i
+                );
+                assert_eq!(
+                    p.out_vals[i].1, b.out_vals[i],
+                    "Product differs at block {}",
+                    i
+                );
+            }
+        }
+    }};
+}
+
+/// Check that a bit-vector pass computes the same sets as the original pass
+macro_rules! compare {
+    ($orig: expr, $bv: expr, $input: expr) => {{
+        let input = std::fs::File::open($input).unwrap();
+        let (_, expected) = SequentialExecutor.run(&$orig, input, false);
+        let input = std::fs::File::open($input).unwrap();
+        let (_, result) = SequentialExecutor.run(&$bv, input, false);
+
+        for (a, b) in result.iter().zip(expected.iter()) {
+            for (i, (x, y)) in a.in_vals.iter().zip(b.in_vals.iter()).enumerate() {
+                if x != y {
+                    log::error!("\n.{}:\n\tIn: {:?}", i, x);
+                    log::error!("\n.{}:\n\tIn: {:?}", i, y);
+                    panic!("Bit-vector pass produced different results");
+                }
+            }
+            for (i, (x, y)) in a.out_vals.iter().zip(b.out_vals.iter()).enumerate() {
+                if x != y {
+                    log::error!("\n.{}:\n\tOut: {:?}", i, x);
+                    log::error!("\n.{}:\n\tOut: {:?}", i, y);
+                    panic!("Bit-vector pass produced different results");
+                }
+            }
+            if a.exit_val != b.exit_val {
+                log::error!("Expected exit value {:?}", b.exit_val);
+                log::error!("Got exit value {:?}", a.exit_val);
+                panic!("Bit-vector pass produced different results");
+            }
+        }
+    }};
+}
+
+/// Check that the gen/kill form of a pass agrees with its transfer function
+macro_rules! check_gen_kill {
+    ($pass: expr, $input: expr) => {{
+        let pass = $pass;
+        assert!(pass.as_gen_kill().is_some());
+
+        let input = std::fs::File::open($input).unwrap();
+        let (_, result) = SequentialExecutor.run(&pass, input, false);
+
+        for d in result {
+            for (i, (x, y)) in d.in_vals.iter().zip(d.out_vals.iter()).enumerate() {
+                let expected = pass.transfer(d.cfg.func().get(i), x);
+                if expected != *y {
+                    log::error!("\n.{}:\n\tIn: {:?}", i, x);
+                    log::error!("\n.{}:\n\tOut: {:?}", i, y);
+                    log::error!("\n.{}:\n\tTransfer: {:?}", i, expected);
+                    panic!("Gen/kill sets disagree with the transfer function");
+                }
+            }
+        }
+    }};
+}
+
+/// Check that replaying a pass one instruction at a time reproduces the block
+/// level solution
+macro_rules! check_instrs {
+    ($pass: expr, $input: expr) => {{
+        let pass = $pass;
+        let input = std::fs::File::open($input).unwrap();
+        let (_, result) = SequentialExecutor.run(&pass, input, false);
+
+        for d in result {
+            for (i, vals) in d.instr_vals(&pass).into_iter().enumerate() {
+                let (first, last) = (vals.first().unwrap(), vals.last().unwrap());
+                let (before, after) = if pass.reversed() {
+                    (&d.out_vals[i], &d.in_vals[i])
+                } else {
+                    (&d.in_vals[i], &d.out_vals[i])
+                };
+
+                if first != before || last != after {
+                    log::error!(
+                        "\n.{}:\n\tIn: {:?}\n\tOut: {:?}",
+                        i,
+                        d.in_vals[i],
+                        d.out_vals[i]
+                    );
+                    log::error!("\n.{}:\n\tInstructions: {:?}", i, vals);
+                    panic!("Instruction-level values disagree with block values");
+                }
+            }
+        }
+    }};
+}
+
+/// Check that the immediate dominator of every block is the strict dominator
+/// that is dominated by all the others, and that the dominance frontier of a
+/// block holds exactly the blocks that have a predecessor it dominates without
+/// being strictly dominated by it
+fn check_dominator_tree(path: &std::path::Path) {
+    let input = std::fs::File::open(path).unwrap();
+    let (_, result) = SequentialExecutor.run(&Dominators, input, false);
+
+    for d in result {
+        let tree = DominatorTree::new(&d);
+
+        // Unreachable blocks are not dominated by anything
+        let dominates = |a: usize, b: usize| match &d.out_vals[b] {
+            BlockSet::Full => false,
+            BlockSet::Finite(doms) => doms.contains(&a),
+        };
+        for b in 0..d.cfg.len() {
+            for f in 0..d.cfg.len() {
+                let expected =
+                    d.cfg.preds(f).iter().any(|&p| dominates(b, p)) && (f == b || !dominates(b, f));
+                assert_eq!(
+                    tree.frontier(b).contains(&f),
+                    expected,
+                    "Wrong dominance frontier {:?} of block {}",
+                    tree.frontier(b),
+                    b
+                );
+            }
+        }
+
+        for (i, doms) in d.out_vals.iter().enumerate() {
+            let BlockSet::Finite(doms) = doms else {
+                assert_eq!(tree.idom(i), None);
+                continue;
+            };
+
+            let mut strict = doms.clone();
+            strict.remove(&i);
+            match tree.idom(i) {
+                None => assert!(strict.is_empty(), "Block {} has no immediate dominator", i),
+                Some(idom) => {
+                    assert!(tree.children(idom).contains(&i));
+                    assert_eq!(
+                        d.out_vals[idom],
+                        BlockSet::Finite(strict),
+                        "Wrong immediate dominator {} of block {}",
+                        idom,
+                        i
+                    );
+                }
+            }
+        }
+    }
+}
+
+/// Check that every cycle of each CFG, forwards and backwards, passes through a
+/// loop head, so that widening only at loop heads terminates
+fn check_loop_heads(path: &std::path::Path) {
+    let input = std::fs::File::open(path).unwrap();
+    let (_, forwards) = SequentialExecutor.run(&ReachingDefs, input, false);
+    let input = std::fs::File::open(path).unwrap();
+    let (_, backwards) = SequentialExecutor.run(&LiveVars, input, false);
+
+    for cfg in forwards.iter().chain(backwards.iter()).map(|d| &d.cfg) {
+        let heads = loop_heads(cfg);
+
+        // Without the loop heads the CFG must be acyclic, so repeatedly
+        // removing blocks without remaining predecessors removes every block
+        let mut preds = (0..cfg.len())
+            .map(|i| cfg.preds(i).iter().filter(|&&p| !heads[p]).count())
+            .collect::<Vec<_>>();
+        let mut ready = (0..cfg.len())
+            .filter(|&i| !heads[i] && preds[i] == 0)
+            .collect::<Vec<_>>();
+        let mut removed = 0;
+        while let Some(i) = ready.pop() {
+            removed += 1;
+            for j in cfg.succs(i) {
+                if !heads[j] {
+                    preds[j] -= 1;
+                    if preds[j] == 0 {
+                        ready.push(j);
+                    }
+                }
+            }
+        }
+
+        let heads = heads.iter().filter(|&&h| h).count();
+        assert_eq!(
+            removed + heads,
+            cfg.len(),
+            "A cycle of @{} avoids every loop head",
+            cfg.name()
+        );
+    }
+}
+
+/// A program that compares an argument with a constant and branches on it
+const EQUALITY: &str = r#"
+@main(x: int) {
+  five: int = const 5;
+  c: bool = eq x five;
+  br c .then .else;
+.then:
+  print x;
+  ret;
+.else:
+  print x;
+}
+"#;
+
+/// Check that constant propagation learns the condition of a branch along each
+/// edge, and the value of `x` along the edge where it equals a constant
+fn check_edge_refinement() {
+    let (_, result) = SequentialExecutor.run(&ConstProp, EQUALITY.as_bytes(), false);
+    let d = &result[0];
+    let block = |label: &str| {
+        (0..d.cfg.len())
+            .find(|&i| block_label(d.cfg.func().get(i)) == Some(label))
+            .unwrap()
+    };
+    let constant = |lit: Literal| Some(Value::Const(lit.into()));
+
+    let then = &d.in_vals[block("then")];
+    assert_eq!(then.get("c").cloned(), constant(Literal::Bool(true)));
+    assert_eq!(then.get("x").cloned(), constant(Literal::Int(5)));
+
+    let other = &d.in_vals[block("else")];
+    assert_eq!(other.get("c").cloned(), constant(Literal::Bool(false)));
+    assert_eq!(other.get("x").cloned(), Some(Value::Any));
+}
+
+/// A program where `a` is defined on every path to its use, `b` on only one,
+/// and `d` on none. The use of `e` is never reached.
+const UNINIT: &str = r#"
+@main(c: bool) {
+  br c .left .right;
+.left:
+  a: int = const 1;
+  b: int = const 2;
+  jmp .join;
+.right:
+  a: int = const 3;
+  jmp .join;
+.join:
+  print a b d;
+  ret;
+.dead:
+  print e;
+}
+"#;
+
+/// Check the exact uses reported by the uninitialized variable checker
+fn check_uninit() {
+    let (_, result) = SequentialExecutor.run(&UninitDefs, UNINIT.as_bytes(), false);
+    let d = &result[0];
+    let block = |label: &str| {
+        (0..d.cfg.len())
+            .find(|&i| block_label(d.cfg.func().get(i)) == Some(label))
+            .unwrap()
+    };
+
+    let uses = UninitUse::find(d)
+        .into_iter()
+        .map(|u| (u.block, u.name, u.defs))
+        .collect::<Vec<_>>();
+    let b = ReachingDefinition {
+        name: "b".to_string(),
+        block: block("left"),
+        instr: Some(1),
+    };
+    assert_eq!(
+        uses,
+        vec![
+            (block("join"), "b".to_string(), vec![b]),
+            (block("join"), "d".to_string(), vec![]),
+        ]
+    );
+}
+
+/// A program that defines `x` on only one branch, and reads it after the join
+/// only on the path that defined it
+const PARTIAL: &str = r#"
+@main(n: int) {
+  zero: int = const 0;
+  c: bool = gt n zero;
+  br c .def .skip;
+.def:
+  x: int = const 7;
+  jmp .join;
+.skip:
+  jmp .join;
+.join:
+  br c .use .end;
+.use:
+  y: int = add x x;
+  print y;
+.end:
+  print n;
+}
+"#;
+
+/// Check that constant propagation treats a variable missing on one path as
+/// agreeing with the others, so `y` is folded, and that the output is the same
+/// on both paths
+fn check_partial_defs() {
+    let prog = load_program(PARTIAL.as_bytes());
+    let folded =
+        Transform::ConstFold.apply(&Executor::Sequential, load_program(PARTIAL.as_bytes()));
+
+    assert!(
+        folded.functions[0].instrs.iter().any(|code| matches!(
+            code,
+            Code::Instruction(Instruction::Constant {
+                dest,
+                value: Literal::Int(14),
+                ..
+            }) if dest == "y"
+        )),
+        "y is not folded to a constant"
+    );
+    for n in ["1", "0"] {
+        let args = [n.to_string()];
+        assert_eq!(
+            Interpreter::new(&folded).run(&args, &mut ()),
+            Interpreter::new(&prog).run(&args, &mut ()),
+            "Folding a partially defined variable changes the output"
+        );
+    }
+}
+
+/// Check that the interval of a constant contains it, including the smallest
+/// and largest integers, which have infinite bounds
+fn check_interval_constants() {
+    for a in [i64::MIN, i64::MIN + 1, -1, 0, 1, i64::MAX - 1, i64::MAX] {
+        assert!(
+            Interval::constant(a).contains(a),
+            "The interval of {} does not contain it",
+            a
+        );
+    }
+}
+
+/// A program whose branch condition is a constant, so `.no` never runs and `x`
+/// is only ever 1 at the join
+const CONSTANT_BRANCH: &str = r#"
+@main {
+  t: bool = const true;
+  br t .yes .no;
+.yes:
+  x: int = const 1;
+  jmp .join;
+.no:
+  x: int = const 2;
+  jmp .join;
+.join:
+  print x;
+}
+"#;
+
+/// Check that SCCP finds exactly the blocks behind a constant branch
+/// unreachable, and that leaving them out makes a constant that plain constant
+/// propagation misses
+fn check_sccp() {
+    let (_, result) = SequentialExecutor.run(&Sccp, CONSTANT_BRANCH.as_bytes(), false);
+    let d = &result[0];
+    let block = |label: &str| {
+        (0..d.cfg.len())
+            .find(|&i| block_label(d.cfg.func().get(i)) == Some(label))
+            .unwrap()
+    };
+    assert_eq!(Sccp::unreachable(d), vec![block("no")]);
+
+    let one = Value::Const(Literal::Int(1).into());
+    match &d.in_vals[block("join")] {
+        SccpVal::Reachable(vals) => assert_eq!(vals.get("x"), Some(&one)),
+        SccpVal::Unreachable => panic!("The join is found unreachable"),
+    }
+
+    let (_, result) = SequentialExecutor.run(&ConstProp, CONSTANT_BRANCH.as_bytes(), false);
+    assert_eq!(result[0].in_vals[block("join")].get("x"), Some(&Value::Any));
+}
+
+/// A program that computes `add x y` three times, the last time into the
+/// variable that already holds it
+const RECOMPUTED: &str = r#"
+@main(x: int, y: int) {
+  a: int = add x y;
+  b: int = add x y;
+  a: int = add x y;
+  print a b;
+}
+"#;
+
+/// Check that CSE copies a recomputed expression from the variable holding it,
+/// and leaves alone a recomputation into that same variable
+fn check_cse() {
+    let prog = Transform::Cse.apply(&Executor::Sequential, load_program(RECOMPUTED.as_bytes()));
+    let ops: Vec<_> = prog.functions[0]
+        .instrs
+        .iter()
+        .filter_map(|code| match code {
+            Code::Instruction(Instruction::Value { dest, op, args, .. }) => {
+                Some((dest.as_str(), *op, args.clone()))
+            }
+            _ => None,
+        })
+        .collect();
+
+    let xy = vec!["x".to_string(), "y".to_string()];
+    assert_eq!(
+        ops,
+        [
+            ("a", ValueOps::Add, xy.clone()),
+            ("b", ValueOps::Id, vec!["a".to_string()]),
+            ("a", ValueOps::Add, xy),
+        ]
+    );
+}
+
+/// Check that a transformation agrees between executors and that applying it
+/// again changes nothing
+fn check_transform(transform: Transform, path: &std::path::Path) {
+    let apply = |executor: &Executor, prog: Program| {
+        serde_json::to_string(&transform.apply(executor, prog)).unwrap()
+    };
+    let load = || load_program(std::fs::File::open(path).unwrap());
+
+    let expected = apply(&Executor::Sequential, load());
+    for executor in Executor::iter() {
+        assert_eq!(
+            apply(&executor, load()),
+            expected,
+            "Transformation {} with executor {} disagrees",
+            transform,
+            executor
+        );
+    }
+
+    let again = apply(
+        &Executor::Sequential,
+        serde_json::from_str(&expected).unwrap(),
+    );
+    assert_eq!(
+        again, expected,
+        "Transformation {} is not idempotent",
+        transform
+    );
+}
+
+/// Check that every transformation preserves the printed output of a
+/// benchmark. Benchmarks the interpreter cannot run, such as those using
+/// pointer operations, are skipped with a warning.
+fn check_output(path: &std::path::Path) {
+    let source = std::fs::read_to_string(path).unwrap();
+    let args = bench_args(&source);
+    let prog = load_program(source.as_bytes());
+
+    let expected = match Interpreter::new(&prog).run(&args, &mut ()) {
+        Ok(output) => output,
+        Err(e) => {
+            log::warn!("Skipping {}: {}", path.display(), e);
+            return;
+        }
+    };
+
+    for transform in Transform::iter() {
+        let prog = transform.apply(&Executor::Sequential, prog.clone());
+        assert_eq!(
+            Interpreter::new(&prog).run(&args, &mut ()),
+            Ok(expected.clone()),
+            "Transformation {} changes the output",
+            transform
+        );
+    }
+}
+
+/// Check that the facts of each pass hold in a run of a benchmark
+fn check_sound(path: &std::path::Path) {
+    let source = std::fs::read_to_string(path).unwrap();
+    match check_soundness(&source) {
+        Ok(violations) => {
+            for violation in violations.iter() {
+                log::error!("{}", violation);
+            }
+            assert!(violations.is_empty(), "Unsound facts");
+        }
+        Err(e) => log::warn!("Skipping {}: {}", path.display(), e),
+    }
+}
+
+/// Check that every executor computes the same solution of every pass
+fn check_executors(path: &std::path::Path) {
+    for pass in Pass::iter() {
+        match pass {
+            Pass::ReachingDefinitions => test!(ReachingDefs, path),
+            Pass::LiveVariables => test!(LiveVars, path),
+            Pass::ConstProp => test!(ConstProp, path),
+            Pass::AvailableExpr => test!(AvailableExpr, path),
+            Pass::ReachingDefinitionsBv => test!(ReachingDefsBv::default(), path),
+            Pass::LiveVariablesBv => test!(LiveVarsBv::default(), path),
+            Pass::AvailableExprBv => test!(AvailableExprBv::default(), path),
+            Pass::Dominators => test!(Dominators, path),
+            Pass::VeryBusyExprs => test!(VeryBusyExprs, path),
+            Pass::Intervals => check_fixpoint!(IntervalAnalysis::default(), path),
+            Pass::Sign => test!(Sign, path),
+            Pass::Parity => test!(Parity, path),
+            Pass::SignParity => test!(Product::new(Sign, Parity), path),
+            Pass::Sccp => test!(Sccp, path),
+        };
+    }
+}
+
+/// Check that random programs of several shapes run to completion and that
+/// every executor agrees on them
+fn check_generated() {
+    let configs = [
+        GenConfig::default(),
+        GenConfig {
+            funcs: 3,
+            blocks: 30,
+            branching: 4,
+            loop_depth: 3,
+            ..GenConfig::default()
+        },
+        GenConfig {
+            funcs: 2,
+            blocks: 60,
+            branching: 3,
+            loop_depth: 2,
+            vars: 4,
+            instrs: 2,
+        },
+    ];
+
+    let dir = std::path::Path::new("./target/generated");
+    std::fs::create_dir_all(dir).unwrap();
+    for (i, config) in configs.iter().enumerate() {
+        for seed in 0..5 {
+            let source = generate(config, seed);
+            let path = dir.join(format!("gen{}-{}.bril", i, seed));
+            log::info!("Test {}", path.display());
+            std::fs::write(&path, &source).unwrap();
+
+            let prog = load_program(source.as_bytes());
+            if let Err(e) = Interpreter::new(&prog).run(&[], &mut ()) {
+                panic!("{} does not run: {}", path.display(), e);
+            }
+            check_executors(&path);
+        }
+    }
+}
+
 fn main() {
     // Loop through every *.bril file in the core/ directory
     // and run every pass with every executor
@@ -86,17 +725,51 @@ fn main() {
     #[cfg(debug_assertions)]
     log::warn!("Running performance benchmarks in debug mode. This may be very slow.");
 
+    if !args.interp && !args.sound {
+        check_interval_constants();
+        check_edge_refinement();
+        check_sccp();
+        check_cse();
+        check_uninit();
+        check_partial_defs();
+        check_generated();
+    }
+
     for entry in dir {
         let entry = entry.unwrap();
         if entry.path().extension().unwrap() == "bril" {
             log::info!("Test {}", entry.path().display(),);
-            for pass in Pass::iter() {
-                match pass {
-                    Pass::ReachingDefinitions => test!(ReachingDefs, entry.path()),
-                    Pass::LiveVariables => test!(LiveVars, entry.path()),
-                    Pass::ConstProp => test!(ConstProp, entry.path()),
-                    Pass::AvailableExpr => test!(AvailableExpr, entry.path()),
-                };
+            if args.interp {
+                check_output(&entry.path());
+                continue;
+            }
+            if args.sound {
+                check_sound(&entry.path());
+                continue;
+            }
+
+            check_executors(&entry.path());
+
+            compare!(ReachingDefs, ReachingDefsBv::default(), entry.path());
+            compare!(LiveVars, LiveVarsBv::default(), entry.path());
+            compare!(AvailableExpr, AvailableExprBv::default(), entry.path());
+
+            check_gen_kill!(ReachingDefs, entry.path());
+            check_gen_kill!(LiveVars, entry.path());
+            check_gen_kill!(AvailableExpr, entry.path());
+
+            check_instrs!(ReachingDefs, entry.path());
+            check_instrs!(ReachingDefsBv::default(), entry.path());
+            check_instrs!(LiveVars, entry.path());
+            check_instrs!(ConstProp, entry.path());
+            check_instrs!(AvailableExpr, entry.path());
+            check_instrs!(VeryBusyExprs, entry.path());
+
+            check_dominator_tree(&entry.path());
+            check_loop_heads(&entry.path());
+            check_product!(Sign, Parity, entry.path());
+            for transform in Transform::iter() {
+                check_transform(transform, &entry.path());
             }
         }
     }
@@ -0,0 +1,247 @@
+use rand::{Rng, SeedableRng, rngs::StdRng};
+use std::fmt::Write;
+
+/// Shape of the random programs made by `generate`
+#[derive(Debug, Clone)]
+pub struct GenConfig {
+    /// Number of functions, including `main`
+    pub funcs: usize,
+    /// Number of blocks of each function, not counting the entry block
+    pub blocks: usize,
+    /// Maximum number of successors of a conditional
+    pub branching: usize,
+    /// Maximum nesting depth of loops
+    pub loop_depth: usize,
+    /// Number of integer variables of each function
+    pub vars: usize,
+    /// Number of random instructions at the start of each block
+    pub instrs: usize,
+}
+
+impl Default for GenConfig {
+    fn default() -> Self {
+        Self {
+            funcs: 1,
+            blocks: 50,
+            branching: 2,
+            loop_depth: 2,
+            vars: 8,
+            instrs: 4,
+        }
+    }
+}
+
+/// Arithmetic operations of random instructions. Division is left out so that
+/// programs never trap.
+const OPS: [&str; 4] = ["add", "sub", "mul", "id"];
+
+/// Comparisons of random conditions
+const CMPS: [&str; 5] = ["eq", "lt", "gt", "le", "ge"];
+
+/// Builds the body of a single function in Bril text form
+struct Builder<'a> {
+    config: &'a GenConfig,
+    rng: &'a mut StdRng,
+    /// Index of the function being built
+    func: usize,
+    code: String,
+    /// Number of labels so far
+    labels: usize,
+    /// Number of fresh condition and loop variables so far
+    temps: usize,
+}
+
+impl Builder<'_> {
+    fn fresh_label(&mut self) -> String {
+        self.labels += 1;
+        format!("b{}", self.labels)
+    }
+
+    fn fresh_temp(&mut self, prefix: &str) -> String {
+        self.temps += 1;
+        format!("{}{}", prefix, self.temps)
+    }
+
+    fn var(&mut self) -> String {
+        format!("v{}", self.rng.gen_range(0..self.config.vars))
+    }
+
+    fn line(&mut self, line: String) {
+        writeln!(self.code, "  {};", line).unwrap();
+    }
+
+    /// Start a new block with some random instructions
+    fn start(&mut self, label: &str) {
+        writeln!(self.code, ".{}:", label).unwrap();
+        for _ in 0..self.config.instrs {
+            self.instr();
+        }
+    }
+
+    /// A random instruction writing one of the variables. Only `main` calls
+    /// other functions, so the call graph is acyclic and runs stay short.
+    fn instr(&mut self) {
+        let dest = self.var();
+        let (a, b) = (self.var(), self.var());
+
+        let line = if self.func == 0 && self.config.funcs > 1 && self.rng.gen_ratio(1, 8) {
+            let callee = self.rng.gen_range(1..self.config.funcs);
+            format!("{}: int = call @f{} {}", dest, callee, a)
+        } else if self.rng.gen_ratio(1, 4) {
+            format!("{}: int = const {}", dest, self.rng.gen_range(-100..100))
+        } else {
+            match OPS[self.rng.gen_range(0..OPS.len())] {
+                "id" => format!("{}: int = id {}", dest, a),
+                op => format!("{}: int = {} {} {}", dest, op, a, b),
+            }
+        };
+        self.line(line);
+    }
+
+    /// A fresh condition on two random variables
+    fn cond(&mut self) -> String {
+        let cond = self.fresh_temp("c");
+        let cmp = CMPS[self.rng.gen_range(0..CMPS.len())];
+        let (a, b) = (self.var(), self.var());
+        self.line(format!("{}: bool = {} {} {}", cond, cmp, a, b));
+        cond
+    }
+
+    /// Add about `budget` blocks after the current one, which is left open for
+    /// more instructions
+    fn region(&mut self, mut budget: usize, depth: usize) {
+        while budget > 0 {
+            let can_loop = depth < self.config.loop_depth && budget >= 3;
+            let can_branch = self.config.branching >= 2 && budget >= 3;
+
+            budget -= match self.rng.gen_range(0..3) {
+                0 if can_loop => self.counted_loop(budget, depth),
+                1 if can_branch => self.branch(budget, depth),
+                _ => {
+                    let next = self.fresh_label();
+                    self.line(format!("jmp .{}", next));
+                    self.start(&next);
+                    1
+                }
+            };
+        }
+    }
+
+    /// A loop that runs a small constant number of times, with a random
+    /// region as its body. Returns the number of blocks used.
+    fn counted_loop(&mut self, budget: usize, depth: usize) -> usize {
+        let inner = self.rng.gen_range(0..=budget - 3);
+        let (head, body, exit) = (self.fresh_label(), self.fresh_label(), self.fresh_label());
+        let counter = self.fresh_temp("i");
+        let bound = self.rng.gen_range(1..=4);
+
+        self.line(format!("{}: int = const 0", counter));
+        self.line(format!("jmp .{}", head));
+
+        writeln!(self.code, ".{}:", head).unwrap();
+        let (limit, cond) = (self.fresh_temp("n"), self.fresh_temp("c"));
+        self.line(format!("{}: int = const {}", limit, bound));
+        self.line(format!("{}: bool = lt {} {}", cond, counter, limit));
+        self.line(format!("br {} .{} .{}", cond, body, exit));
+
+        self.start(&body);
+        self.region(inner, depth + 1);
+        let one = self.fresh_temp("k");
+        self.line(format!("{}: int = const 1", one));
+        self.line(format!("{}: int = add {} {}", counter, counter, one));
+        self.line(format!("jmp .{}", head));
+
+        self.start(&exit);
+        3 + inner
+    }
+
+    /// A conditional with between 2 and `branching` arms, each a random region,
+    /// that join afterwards. Returns the number of blocks used.
+    fn branch(&mut self, budget: usize, depth: usize) -> usize {
+        // k arms take k - 2 extra test blocks, k arm blocks and a join block
+        let max_arms = self.config.branching.min(budget.div_ceil(2)).max(2);
+        let arms = self.rng.gen_range(2..=max_arms);
+        let mut rest = budget.saturating_sub(2 * arms - 1);
+        let labels: Vec<_> = (0..arms).map(|_| self.fresh_label()).collect();
+        let join = self.fresh_label();
+
+        // A chain of tests picks the arm
+        for k in 0..arms - 1 {
+            let cond = self.cond();
+            let next = if k == arms - 2 {
+                labels[k + 1].clone()
+            } else {
+                self.fresh_label()
+            };
+            self.line(format!("br {} .{} .{}", cond, labels[k], next));
+            if k < arms - 2 {
+                writeln!(self.code, ".{}:", next).unwrap();
+            }
+        }
+
+        let mut used = 2 * arms - 1;
+        for label in labels {
+            let inner = self.rng.gen_range(0..=rest);
+            rest -= inner;
+            used += inner;
+
+            self.start(&label);
+            self.region(inner, depth);
+            self.line(format!("jmp .{}", join));
+        }
+
+        self.start(&join);
+        used
+    }
+}
+
+/// Generate a random program in Bril text form. Every variable is defined in
+/// the entry block, every loop is counted and only `main` makes calls, so the
+/// program always runs to completion and prints the variables of `main` at the
+/// end. The same seed always gives the same program.
+pub fn generate(config: &GenConfig, seed: u64) -> String {
+    let mut rng = StdRng::seed_from_u64(seed);
+    let config = GenConfig {
+        funcs: config.funcs.max(1),
+        vars: config.vars.max(1),
+        ..config.clone()
+    };
+    let mut prog = String::new();
+
+    for func in 0..config.funcs {
+        let mut builder = Builder {
+            config: &config,
+            rng: &mut rng,
+            func,
+            code: String::new(),
+            labels: 0,
+            temps: 0,
+        };
+
+        // Functions other than main take their first variable as an argument
+        let first = if func == 0 { 0 } else { 1 };
+        for v in first..config.vars {
+            let value = builder.rng.gen_range(-100..100);
+            builder.line(format!("v{}: int = const {}", v, value));
+        }
+        let entry = builder.fresh_label();
+        builder.line(format!("jmp .{}", entry));
+        builder.start(&entry);
+        builder.region(config.blocks.saturating_sub(1), 0);
+
+        let vars = (0..config.vars)
+            .map(|v| format!("v{}", v))
+            .collect::<Vec<_>>();
+        if func == 0 {
+            builder.line(format!("print {}", vars.join(" ")));
+            writeln!(prog, "@main {{").unwrap();
+        } else {
+            builder.line("ret v0".to_string());
+            writeln!(prog, "@f{}(v0: int): int {{", func).unwrap();
+        }
+        prog += &builder.code;
+        writeln!(prog, "}}").unwrap();
+    }
+
+    prog
+}
@@ -0,0 +1,111 @@
+use bril_utils::CFG;
+
+/// Compute the blocks of a CFG in reverse postorder. The depth-first search
+/// starts from entry blocks, then from blocks without predecessors, and finally
+/// from any block that is still unvisited, so every block is included exactly
+/// once. On a reversed CFG this is the postorder of the original CFG.
+pub(crate) fn reverse_postorder(cfg: &CFG) -> Vec<usize> {
+    let n = cfg.len();
+    let roots = (0..n)
+        .filter(|&i| cfg.func().get(i).is_entry() || cfg.preds(i).is_empty())
+        .chain(0..n);
+
+    let mut visited = vec![false; n];
+    let mut postorder = Vec::with_capacity(n);
+
+    for root in roots {
+        if visited[root] {
+            continue;
+        }
+        visited[root] = true;
+
+        let mut stack = vec![(root, cfg.succs(root).into_iter())];
+        while let Some((i, succs)) = stack.last_mut() {
+            if let Some(j) = succs.next() {
+                if !visited[j] {
+                    visited[j] = true;
+                    stack.push((j, cfg.succs(j).into_iter()));
+                }
+            } else {
+                postorder.push(*i);
+                stack.pop();
+            }
+        }
+    }
+
+    postorder.reverse();
+    postorder
+}
+
+/// Compute the strongly connected components of a CFG using Tarjan's algorithm.
+/// Returns the components in topological order of the condensation DAG, along
+/// with the index of the component containing each block.
+pub(crate) fn strongly_connected_components(cfg: &CFG) -> (Vec<Vec<usize>>, Vec<usize>) {
+    let n = cfg.len();
+    let mut index = vec![usize::MAX; n];
+    let mut lowlink = vec![0; n];
+    let mut on_stack = vec![false; n];
+    let mut stack = Vec::new();
+    let mut next = 0;
+
+    let mut comps = Vec::new();
+    let mut comp_of = vec![usize::MAX; n];
+
+    for root in 0..n {
+        if index[root] != usize::MAX {
+            continue;
+        }
+
+        index[root] = next;
+        lowlink[root] = next;
+        next += 1;
+        stack.push(root);
+        on_stack[root] = true;
+
+        let mut calls = vec![(root, cfg.succs(root).into_iter())];
+        while let Some((i, succs)) = calls.last_mut() {
+            let i = *i;
+            if let Some(j) = succs.next() {
+                if index[j] == usize::MAX {
+                    index[j] = next;
+                    lowlink[j] = next;
+                    next += 1;
+                    stack.push(j);
+                    on_stack[j] = true;
+                    calls.push((j, cfg.succs(j).into_iter()));
+                } else if on_stack[j] {
+                    lowlink[i] = lowlink[i].min(index[j]);
+                }
+            } else {
+                calls.pop();
+                if let Some(&(p, _)) = calls.last() {
+                    lowlink[p] = lowlink[p].min(lowlink[i]);
+                }
+
+                if lowlink[i] == index[i] {
+                    // i is the root of a component
+                    let mut comp = Vec::new();
+                    loop {
+                        let j = stack.pop().unwrap();
+                        on_stack[j] = false;
+                        comp_of[j] = comps.len();
+                        comp.push(j);
+                        if j == i {
+                            break;
+                        }
+                    }
+                    comps.push(comp);
+                }
+            }
+        }
+    }
+
+    // Tarjan's algorithm emits components in reverse topological order
+    comps.reverse();
+    let m = comps.len();
+    for c in comp_of.iter_mut() {
+        *c = m - 1 - *c;
+    }
+
+    (comps, comp_of)
+}
@@ -1,10 +1,24 @@
+mod async_parallel;
+mod generator;
+mod graph;
 mod macros;
 mod mixed;
 mod parallel;
 mod passes;
+mod region;
+mod scc;
+mod schedule;
 mod sequential;
+mod soundness;
+mod transform;
 
+pub use async_parallel::AsyncParallelExecutor;
+pub use generator::{GenConfig, generate};
 pub use mixed::MixedExecutor;
 pub use parallel::ParallelExecutor;
-pub use passes::{Executor, Pass};
-pub use sequential::SequentialExecutor;
+pub use passes::{Check, Executor, Format, Pass};
+pub use region::RegionExecutor;
+pub use scc::SccExecutor;
+pub use sequential::{RpoSequentialExecutor, SequentialExecutor};
+pub use soundness::{Violation, check_soundness};
+pub use transform::Transform;
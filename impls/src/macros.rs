@@ -1,19 +1,50 @@
 #[macro_export]
-/// Execute a pass with a given executor
-macro_rules! execute_pass {
-    ($pass: expr, $executor: ident, $input: ident) => {
+/// Call a generic function with the executor selected by an `Executor` as its
+/// first argument. Parallelizing across functions is up to the caller, so that
+/// executor runs each function sequentially.
+macro_rules! with_executor {
+    ($executor: expr, $f: ident($($arg: expr),*)) => {
         match $executor {
-            Executor::Sequential => $crate::SequentialExecutor.run(&$pass, $input, false),
-            Executor::Parallel => $crate::ParallelExecutor.run(&$pass, $input, false),
-            Executor::ParallelizedAcrossFunctions => {
-                $crate::SequentialExecutor.run(&$pass, $input, true)
+            Executor::Sequential | Executor::ParallelizedAcrossFunctions => {
+                $f(&$crate::SequentialExecutor, $($arg),*)
             }
-            Executor::Mixed(thresh) => $crate::MixedExecutor::new(
-                *thresh,
-                $crate::SequentialExecutor,
-                $crate::ParallelExecutor,
-            )
-            .run(&$pass, $input, false),
+            Executor::SequentialRpo => $f(&$crate::RpoSequentialExecutor, $($arg),*),
+            Executor::Parallel => $f(&$crate::ParallelExecutor::default(), $($arg),*),
+            Executor::AsyncParallel => $f(&$crate::AsyncParallelExecutor, $($arg),*),
+            Executor::Mixed(thresh) => $f(
+                &$crate::MixedExecutor::new(
+                    *thresh,
+                    $crate::SequentialExecutor,
+                    $crate::ParallelExecutor::default(),
+                ),
+                $($arg),*
+            ),
+            Executor::Scc => $f(&$crate::SccExecutor::new(false), $($arg),*),
+            Executor::SccParallel => $f(&$crate::SccExecutor::new(true), $($arg),*),
+            Executor::Region(size) => $f(&$crate::RegionExecutor::new(*size), $($arg),*),
         }
     };
 }
+
+#[macro_export]
+/// Execute a pass with a given executor
+macro_rules! execute_pass {
+    ($pass: expr, $executor: ident, $input: ident) => {{
+        fn run<E, P, R>(
+            executor: &E,
+            pass: &P,
+            input: R,
+            par_func_analysis: bool,
+        ) -> (::utils::PassTiming, Vec<::bril_utils::Dataflow<P::Val>>)
+        where
+            E: ::utils::DataflowExecutor<P>,
+            P: ::utils::DataflowSpec + Send + Sync,
+            R: std::io::Read,
+        {
+            executor.run(pass, input, par_func_analysis)
+        }
+
+        let par_func_analysis = matches!($executor, Executor::ParallelizedAcrossFunctions);
+        $crate::with_executor!($executor, run(&$pass, $input, par_func_analysis))
+    }};
+}
@@ -1,5 +1,5 @@
 use bril_utils::{CFG, Dataflow};
-use utils::{DataflowExecutor, DataflowSpec};
+use utils::{DataflowExecutor, DataflowSpec, DataflowStats};
 
 #[derive(Default)]
 /// Dynamically choose between two executors based on the size of the CFG
@@ -29,7 +29,7 @@ where
     Ex1: DataflowExecutor<Pass>,
     Ex2: DataflowExecutor<Pass>,
 {
-    fn cfg(&self, pass: &Pass, cfg: CFG) -> Dataflow<Pass::Val> {
+    fn cfg(&self, pass: &Pass, cfg: CFG) -> (Dataflow<Pass::Val>, DataflowStats) {
         if cfg.len() > self.threshold {
             self.executor2.cfg(pass, cfg)
         } else {
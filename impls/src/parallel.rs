@@ -2,16 +2,29 @@ use bril_utils::{CFG, Dataflow};
 use itertools::Itertools;
 use rayon::prelude::*;
 use std::collections::HashSet;
-use utils::{DataflowExecutor, DataflowSpec};
+use utils::{BlockTransfer, DataflowExecutor, DataflowSpec, DataflowStats};
 
 #[derive(Default)]
-pub struct ParallelExecutor;
+pub struct ParallelExecutor {
+    /// Whether to keep the blocks of every round in the stats
+    record_batches: bool,
+}
+
+impl ParallelExecutor {
+    /// A parallel executor that also records the blocks processed in each
+    /// round, at the cost of a sorted copy of the worklist per round
+    pub fn recording_batches() -> Self {
+        Self {
+            record_batches: true,
+        }
+    }
+}
 
 impl<Pass> DataflowExecutor<Pass> for ParallelExecutor
 where
     Pass: DataflowSpec + Send + Sync,
 {
-    fn cfg(&self, pass: &Pass, cfg: CFG) -> Dataflow<Pass::Val> {
+    fn cfg(&self, pass: &Pass, cfg: CFG) -> (Dataflow<Pass::Val>, DataflowStats) {
         log::debug!("Function {}", cfg.name());
         let cfg = if cfg.reversed() != pass.reversed() {
             cfg.reverse()
@@ -23,11 +36,24 @@ where
 
         let mut out_vals = vec![pass.init(cfg.func()); n];
         let mut in_vals = vec![pass.init(cfg.func()); n];
+        let transfer = BlockTransfer::new(pass, &cfg);
+
+        let mut stats = DataflowStats {
+            pushes: n,
+            ..Default::default()
+        };
 
         let mut worklist: HashSet<_> = (0..n).collect();
 
         while !worklist.is_empty() {
             log::trace!("Worklist: {:?}", worklist);
+            stats.rounds += 1;
+            stats.observe_width(worklist.len());
+            if self.record_batches {
+                stats
+                    .batches
+                    .push(worklist.iter().copied().sorted().collect());
+            }
             // Dispatch the worklist to multiple threads
             let results: Vec<_> = std::mem::take(&mut worklist)
                 .into_par_iter()
@@ -35,15 +61,15 @@ where
                     let i_vals = if cfg.func().get(i).is_entry() {
                         pass.entry(cfg.func())
                     } else {
-                        let inputs = cfg
-                            .preds(i)
+                        let preds = cfg.preds(i).iter().copied().collect_vec();
+                        let inputs = preds
                             .iter()
-                            .map(|&j| out_vals[j].clone())
+                            .map(|&j| pass.edge_transfer(cfg.func(), j, i, out_vals[j].clone()))
                             .collect_vec();
-                        pass.meet(&inputs)
+                        pass.meet_edges(cfg.func(), i, &preds, &inputs)
                     };
 
-                    let o_vals = pass.transfer(cfg.func().get(i), &i_vals);
+                    let o_vals = transfer.apply(i, &i_vals, &out_vals[i]);
 
                     (
                         i,
@@ -59,6 +85,10 @@ where
 
             for (i, i_vals, changed) in results {
                 in_vals[i] = i_vals;
+                stats.transfers += 1;
+                if !cfg.func().get(i).is_entry() {
+                    stats.meets += 1;
+                }
 
                 if let Some((result_succs, o_vals)) = changed {
                     // If the out value changed, add successors to the worklist
@@ -66,7 +96,9 @@ where
                     out_vals[i] = o_vals;
                     // Add successors to the worklist
                     for j in result_succs {
-                        worklist.insert(j);
+                        if worklist.insert(j) {
+                            stats.pushes += 1;
+                        }
                     }
                 }
             }
@@ -80,12 +112,16 @@ where
             .collect_vec();
         let exit_val = pass.meet(&exit_val);
         let exit_val = pass.finish(cfg.func(), exit_val);
+        stats.reprocessed = stats.transfers.saturating_sub(n);
 
-        Dataflow {
-            cfg,
-            in_vals,
-            out_vals,
-            exit_val,
-        }
+        (
+            Dataflow {
+                cfg,
+                in_vals,
+                out_vals,
+                exit_val,
+            },
+            stats,
+        )
     }
 }
@@ -1,21 +1,43 @@
-use passes::{AvailableExpr, ConstProp, LiveVars, ReachingDefs};
+use bril_utils::Dataflow;
+use itertools::Itertools;
+use passes::{
+    AvailableExpr, AvailableExprBv, ConstProp, Dominators, IntervalAnalysis, LiveVars, LiveVarsBv,
+    Parity, ReachingDefs, ReachingDefsBv, Sccp, Sign, UninitDefs, UninitUse, VeryBusyExprs,
+};
 use regex::Regex;
 use serde::Serialize;
-use std::{fmt::Display, str::FromStr};
+use std::{
+    fmt::{Debug, Display},
+    str::FromStr,
+};
 use strum::{Display, EnumIter, EnumString};
-use utils::{DataflowExecutor, PassTiming};
+use utils::{
+    DataflowExecutor, DataflowStats, FunctionResult, PassTiming, Product, annotate, block_label,
+    dot_cluster,
+};
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum Executor {
     /// Basic sequential worklist algorithm
     Sequential,
+    /// Sequential worklist algorithm ordered by reverse postorder
+    SequentialRpo,
     /// Parallel worklist algorithm
     Parallel,
+    /// Parallel worklist algorithm without a barrier between rounds
+    AsyncParallel,
     /// Within each function, worklist proceeds sequentially. Parallelization
     /// occurs across functions.
     ParallelizedAcrossFunctions,
     /// Mixed worklist algorithm
     Mixed(usize),
+    /// Solve strongly connected components one at a time in topological order
+    Scc,
+    /// Solve independent strongly connected components in parallel
+    SccParallel,
+    /// Work-stealing worklist algorithm over connected regions of at most the
+    /// given number of blocks
+    Region(usize),
 }
 
 impl FromStr for Executor {
@@ -25,8 +47,12 @@ impl FromStr for Executor {
         // Simple pattern matching
         let simple = match s {
             "sequential" | "seq" => Some(Executor::Sequential),
+            "sequential-rpo" | "seq-rpo" => Some(Executor::SequentialRpo),
             "parallel" | "par" => Some(Executor::Parallel),
+            "async-parallel" | "async" => Some(Executor::AsyncParallel),
             "parallel_across_functions" => Some(Executor::ParallelizedAcrossFunctions),
+            "scc" => Some(Executor::Scc),
+            "scc-par" | "scc-parallel" => Some(Executor::SccParallel),
             _ => None,
         };
 
@@ -35,9 +61,13 @@ impl FromStr for Executor {
         } else {
             // Parse strings with arguments
             let re = Regex::new(r"^mixed-(\d+)$").unwrap();
+            let region_re = Regex::new(r"^region-(\d+)$").unwrap();
             if let Some(caps) = re.captures(s) {
                 let thresh = caps[1].parse().unwrap();
                 Ok(Executor::Mixed(thresh))
+            } else if let Some(caps) = region_re.captures(s) {
+                let size = caps[1].parse().unwrap();
+                Ok(Executor::Region(size))
             } else {
                 Err(format!("Unknown executor {}", s))
             }
@@ -49,9 +79,14 @@ impl Display for Executor {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
             Executor::Sequential => "sequential".fmt(f),
+            Executor::SequentialRpo => "sequential-rpo".fmt(f),
             Executor::Parallel => "parallel".fmt(f),
+            Executor::AsyncParallel => "async-parallel".fmt(f),
             Executor::ParallelizedAcrossFunctions => "parallel_across_functions".fmt(f),
             Executor::Mixed(thresh) => write!(f, "mixed-{thresh}"),
+            Executor::Scc => "scc".fmt(f),
+            Executor::SccParallel => "scc-par".fmt(f),
+            Executor::Region(size) => write!(f, "region-{size}"),
         }
     }
 }
@@ -69,13 +104,19 @@ impl Executor {
     pub fn iter() -> impl Iterator<Item = Self> {
         vec![
             Executor::Sequential,
+            Executor::SequentialRpo,
             Executor::Parallel,
+            Executor::AsyncParallel,
             Executor::ParallelizedAcrossFunctions,
             Executor::Mixed(10),
             Executor::Mixed(15),
             Executor::Mixed(20),
             Executor::Mixed(25),
             Executor::Mixed(30),
+            Executor::Scc,
+            Executor::SccParallel,
+            Executor::Region(16),
+            Executor::Region(64),
         ]
         .into_iter()
     }
@@ -104,28 +145,173 @@ pub enum Pass {
         serialize = "available-exprs"
     )]
     AvailableExpr,
+    /// Reaching definitions over bit vectors
+    #[strum(serialize = "rd-bv", serialize = "reaching-defs-bv")]
+    ReachingDefinitionsBv,
+    /// Live variables over bit vectors
+    #[strum(serialize = "lv-bv", serialize = "live-vars-bv")]
+    LiveVariablesBv,
+    /// Available expressions over bit vectors
+    #[strum(serialize = "available-expr-bv", serialize = "available-exprs-bv")]
+    AvailableExprBv,
+    /// Dominators
+    #[strum(serialize = "dom", serialize = "dominators")]
+    Dominators,
+    /// Very busy expressions
+    #[strum(
+        serialize = "vbe",
+        serialize = "very-busy",
+        serialize = "very-busy-exprs"
+    )]
+    VeryBusyExprs,
+    /// Integer ranges, with widening
+    #[strum(serialize = "intervals", serialize = "interval")]
+    Intervals,
+    /// Signs of integers
+    #[strum(serialize = "sign")]
+    Sign,
+    /// Parities of integers
+    #[strum(serialize = "parity")]
+    Parity,
+    /// Signs and parities of integers in a single fixpoint
+    #[strum(serialize = "sign-parity")]
+    SignParity,
+    /// Sparse conditional constant propagation
+    #[strum(serialize = "sccp")]
+    Sccp,
 }
 
-macro_rules! run {
-    ($executor: ident, $pass: ident, $input: ident) => {{
-        let (timings, data) = $crate::execute_pass!($pass, $executor, $input);
+#[derive(EnumString, EnumIter, Debug, Display, Clone, Copy, PartialEq, Eq)]
+/// How the results of a pass are printed
+pub enum Format {
+    /// Debug representation of each function's results
+    #[strum(serialize = "debug")]
+    Debug,
+    /// A JSON array with one object per function
+    #[strum(serialize = "json")]
+    Json,
+    /// The program in Bril text form, with facts as comments around each block
+    #[strum(serialize = "annotated")]
+    Annotated,
+    /// A Graphviz graph of every function's CFG, with the blocks processed in
+    /// the given round of a round-based executor highlighted
+    #[strum(serialize = "dot")]
+    Dot(Option<usize>),
+}
+
+impl Format {
+    /// Format the results of every function in a program, given the work
+    /// counters of each function
+    pub fn output<Val: Debug + Serialize>(
+        &self,
+        data: &[Dataflow<Val>],
+        stats: &[DataflowStats],
+    ) -> String {
+        match self {
+            Format::Debug => data
+                .iter()
+                .map(|d| format!("{:?}", d))
+                .collect::<Vec<_>>()
+                .join("\n"),
+            Format::Json => {
+                let functions: Vec<_> = data.iter().map(FunctionResult::from).collect();
+                serde_json::to_string_pretty(&functions).unwrap()
+            }
+            Format::Annotated => data.iter().map(annotate).collect::<Vec<_>>().join("\n"),
+            Format::Dot(round) => {
+                let clusters: String = data
+                    .iter()
+                    .zip(stats)
+                    .map(|(d, s)| {
+                        let highlight = round
+                            .and_then(|r| s.batches.get(r))
+                            .map_or(&[][..], Vec::as_slice);
+                        dot_cluster(d, highlight)
+                    })
+                    .collect();
+                format!("digraph {{\n{}}}", clusters)
+            }
+        }
+    }
+}
 
-        let result = data
-            .into_iter()
-            .map(|d| format!("{:?}", d))
-            .collect::<Vec<_>>()
-            .join("\n");
+macro_rules! run {
+    ($executor: ident, $pass: expr, $input: ident, $format: ident) => {{
+        // Rounds are only recorded when the dot output highlights one
+        let (timings, data) = match ($executor, &$format) {
+            (Executor::Parallel, Format::Dot(Some(_))) => {
+                $crate::ParallelExecutor::recording_batches().run(&$pass, $input, false)
+            }
+            _ => $crate::execute_pass!($pass, $executor, $input),
+        };
+        let result = $format.output(&data, &timings.stats);
         (timings, result)
     }};
 }
 
 impl Pass {
+    pub fn execute<R: std::io::Read>(
+        &self,
+        executor: &Executor,
+        input: R,
+        format: Format,
+    ) -> (PassTiming, String) {
+        match self {
+            Pass::ReachingDefinitions => run!(executor, ReachingDefs, input, format),
+            Pass::LiveVariables => run!(executor, LiveVars, input, format),
+            Pass::ConstProp => run!(executor, ConstProp, input, format),
+            Pass::AvailableExpr => run!(executor, AvailableExpr, input, format),
+            Pass::ReachingDefinitionsBv => run!(executor, ReachingDefsBv::default(), input, format),
+            Pass::LiveVariablesBv => run!(executor, LiveVarsBv::default(), input, format),
+            Pass::AvailableExprBv => run!(executor, AvailableExprBv::default(), input, format),
+            Pass::Dominators => run!(executor, Dominators, input, format),
+            Pass::VeryBusyExprs => run!(executor, VeryBusyExprs, input, format),
+            Pass::Intervals => run!(executor, IntervalAnalysis::default(), input, format),
+            Pass::Sign => run!(executor, Sign, input, format),
+            Pass::Parity => run!(executor, Parity, input, format),
+            Pass::SignParity => run!(executor, Product::new(Sign, Parity), input, format),
+            Pass::Sccp => run!(executor, Sccp, input, format),
+        }
+    }
+}
+
+#[derive(EnumString, EnumIter, Debug, Display, Clone, Copy, PartialEq, Eq)]
+/// Diagnostics built on the results of a pass
+pub enum Check {
+    /// Uses of variables that are not defined on every path reaching them
+    #[strum(serialize = "uninit", serialize = "uninitialized")]
+    Uninit,
+    /// Blocks that sparse conditional constant propagation finds unreachable
+    #[strum(serialize = "unreachable")]
+    Unreachable,
+}
+
+impl Check {
+    /// Run the check, returning one diagnostic per line
     pub fn execute<R: std::io::Read>(&self, executor: &Executor, input: R) -> (PassTiming, String) {
         match self {
-            Pass::ReachingDefinitions => run!(executor, ReachingDefs, input),
-            Pass::LiveVariables => run!(executor, LiveVars, input),
-            Pass::ConstProp => run!(executor, ConstProp, input),
-            Pass::AvailableExpr => run!(executor, AvailableExpr, input),
+            Check::Uninit => {
+                let (timings, data) = crate::execute_pass!(UninitDefs, executor, input);
+                let result = data.iter().flat_map(UninitUse::find).join("\n");
+                (timings, result)
+            }
+            Check::Unreachable => {
+                let (timings, data) = crate::execute_pass!(Sccp, executor, input);
+                let result = data
+                    .iter()
+                    .flat_map(|d| {
+                        Sccp::unreachable(d).into_iter().map(move |i| {
+                            match block_label(d.cfg.func().get(i)) {
+                                Some(label) => {
+                                    format!("@{}: .{} is unreachable", d.cfg.name(), label)
+                                }
+                                None => format!("@{}: block {} is unreachable", d.cfg.name(), i),
+                            }
+                        })
+                    })
+                    .join("\n");
+                (timings, result)
+            }
         }
     }
 }
@@ -0,0 +1,240 @@
+use crate::schedule::{SharedStats, TaskState};
+use bril_utils::{CFG, Dataflow};
+use itertools::Itertools;
+use std::{
+    collections::{HashSet, VecDeque},
+    sync::{Arc, Mutex, RwLock},
+};
+use utils::{BlockTransfer, DataflowExecutor, DataflowSpec, DataflowStats};
+
+/// Partition the blocks of a CFG into connected regions of at most `size`
+/// blocks. Regions are grown breadth-first over both predecessor and successor
+/// edges, so blocks that exchange values tend to end up in the same region.
+fn partition(cfg: &CFG, size: usize) -> (Vec<Vec<usize>>, Vec<usize>) {
+    let n = cfg.len();
+    let mut region_of = vec![usize::MAX; n];
+    let mut regions = Vec::new();
+
+    for start in 0..n {
+        if region_of[start] != usize::MAX {
+            continue;
+        }
+
+        let r = regions.len();
+        let mut region = Vec::new();
+        let mut queue = VecDeque::from([start]);
+        region_of[start] = r;
+
+        while let Some(i) = queue.pop_front() {
+            region.push(i);
+            for j in cfg.succs(i).into_iter().chain(cfg.preds(i).iter().copied()) {
+                if region_of[j] == usize::MAX && region.len() + queue.len() < size {
+                    region_of[j] = r;
+                    queue.push_back(j);
+                }
+            }
+        }
+
+        regions.push(region);
+    }
+
+    (regions, region_of)
+}
+
+/// Work-stealing worklist algorithm. The CFG is partitioned into connected
+/// regions, and each region is a rayon task that runs the sequential worklist
+/// algorithm over its own blocks. Changed out values on the boundary of a
+/// region are forwarded to the regions owning the successors.
+pub struct RegionExecutor {
+    /// The maximum number of blocks in a region
+    region_size: usize,
+}
+
+impl RegionExecutor {
+    /// Create a new RegionExecutor with regions of at most `region_size` blocks
+    pub fn new(region_size: usize) -> Self {
+        Self {
+            region_size: region_size.max(1),
+        }
+    }
+}
+
+/// State shared between all region tasks working on a single CFG
+struct Regions<'a, Pass: DataflowSpec> {
+    pass: &'a Pass,
+    cfg: &'a CFG,
+    transfer: BlockTransfer<'a, Pass>,
+    regions: Vec<Vec<usize>>,
+    region_of: Vec<usize>,
+    /// Blocks of each region that need to be (re)processed because of a change
+    /// in another region
+    inboxes: Vec<Mutex<Vec<usize>>>,
+    states: Vec<TaskState>,
+    in_vals: Vec<Mutex<Pass::Val>>,
+    out_vals: Vec<RwLock<Arc<Pass::Val>>>,
+    stats: SharedStats,
+}
+
+impl<'a, Pass> Regions<'a, Pass>
+where
+    Pass: DataflowSpec + Send + Sync,
+{
+    fn new(pass: &'a Pass, cfg: &'a CFG, region_size: usize) -> Self {
+        let n = cfg.len();
+        let init = pass.init(cfg.func());
+        let (regions, region_of) = partition(cfg, region_size);
+        log::debug!("Partitioned {} blocks into {} regions", n, regions.len());
+        let stats = SharedStats::default();
+        stats.push(n);
+
+        Self {
+            pass,
+            cfg,
+            transfer: BlockTransfer::new(pass, cfg),
+            inboxes: regions.iter().map(|r| Mutex::new(r.clone())).collect(),
+            states: regions.iter().map(|_| TaskState::default()).collect(),
+            regions,
+            region_of,
+            in_vals: (0..n).map(|_| Mutex::new(init.clone())).collect(),
+            out_vals: (0..n)
+                .map(|_| RwLock::new(Arc::new(init.clone())))
+                .collect(),
+            stats,
+        }
+    }
+
+    /// Get a snapshot of the current out value of a block
+    fn out_val(&self, i: usize) -> Arc<Pass::Val> {
+        Arc::clone(&self.out_vals[i].read().unwrap())
+    }
+
+    /// Spawn a task for region `r` unless one is already pending
+    fn schedule<'s>(&'s self, scope: &rayon::Scope<'s>, r: usize) {
+        if self.states[r].schedule() {
+            scope.spawn(move |scope| self.process(scope, r));
+        }
+    }
+
+    /// Run the worklist algorithm on region `r` until no block in it changes
+    fn process<'s>(&'s self, scope: &rayon::Scope<'s>, r: usize) {
+        self.states[r].start();
+
+        let mut worklist = VecDeque::new();
+        let mut pending = HashSet::new();
+
+        loop {
+            for i in self.inboxes[r].lock().unwrap().drain(..) {
+                if pending.insert(i) {
+                    worklist.push_back(i);
+                } else {
+                    // Already pending locally
+                    self.stats.pop();
+                }
+            }
+
+            while let Some(i) = worklist.pop_front() {
+                pending.remove(&i);
+                self.stats.pop();
+
+                let in_val = if self.cfg.func().get(i).is_entry() {
+                    self.pass.entry(self.cfg.func())
+                } else {
+                    let preds = self.cfg.preds(i).iter().copied().collect_vec();
+                    let inputs = preds
+                        .iter()
+                        .map(|&j| {
+                            let val = self.out_val(j).as_ref().clone();
+                            self.pass.edge_transfer(self.cfg.func(), j, i, val)
+                        })
+                        .collect_vec();
+                    self.stats.meet();
+                    self.pass.meet_edges(self.cfg.func(), i, &preds, &inputs)
+                };
+
+                let old_vals = self.out_val(i);
+                let new_vals = self.transfer.apply(i, &in_val, &old_vals);
+                self.stats.transfer();
+                *self.in_vals[i].lock().unwrap() = in_val;
+
+                // Only the task owning the region of block i writes its out value
+                if *old_vals != new_vals {
+                    log::trace!("New values for block {}: {:?}", i, new_vals);
+                    *self.out_vals[i].write().unwrap() = Arc::new(new_vals);
+                    for j in self.cfg.succs(i) {
+                        let s = self.region_of[j];
+                        if s == r {
+                            if pending.insert(j) {
+                                self.stats.push(1);
+                                worklist.push_back(j);
+                            }
+                        } else {
+                            self.stats.push(1);
+                            self.inboxes[s].lock().unwrap().push(j);
+                            self.schedule(scope, s);
+                        }
+                    }
+                }
+            }
+
+            if self.states[r].finish() {
+                break;
+            }
+        }
+    }
+
+    fn into_vals(self) -> (Vec<Pass::Val>, Vec<Pass::Val>, DataflowStats) {
+        let n = self.cfg.len();
+        let in_vals = self
+            .in_vals
+            .into_iter()
+            .map(|v| v.into_inner().unwrap())
+            .collect();
+        let out_vals = self
+            .out_vals
+            .into_iter()
+            .map(|v| Arc::unwrap_or_clone(v.into_inner().unwrap()))
+            .collect();
+        (in_vals, out_vals, self.stats.into_stats(n))
+    }
+}
+
+impl<Pass> DataflowExecutor<Pass> for RegionExecutor
+where
+    Pass: DataflowSpec + Send + Sync,
+{
+    fn cfg(&self, pass: &Pass, cfg: CFG) -> (Dataflow<Pass::Val>, DataflowStats) {
+        log::debug!("Function {}", cfg.name());
+        let cfg = if cfg.reversed() != pass.reversed() {
+            cfg.reverse()
+        } else {
+            cfg
+        };
+
+        let regions = Regions::new(pass, &cfg, self.region_size);
+        rayon::scope(|scope| {
+            for r in 0..regions.regions.len() {
+                regions.schedule(scope, r);
+            }
+        });
+        let (in_vals, out_vals, stats) = regions.into_vals();
+
+        // The exit value can be computed by meeting all the out values of exit block(s)
+        let exit_val = cfg
+            .exits()
+            .into_iter()
+            .map(|i| out_vals[i].clone())
+            .collect_vec();
+        let exit_val = pass.meet(&exit_val);
+        let exit_val = pass.finish(cfg.func(), exit_val);
+
+        (
+            Dataflow {
+                cfg,
+                in_vals,
+                out_vals,
+                exit_val,
+            },
+            stats,
+        )
+    }
+}
@@ -0,0 +1,194 @@
+use crate::graph::{reverse_postorder, strongly_connected_components};
+use bril_utils::{CFG, Dataflow};
+use itertools::Itertools;
+use rayon::prelude::*;
+use std::collections::VecDeque;
+use utils::{BlockTransfer, DataflowExecutor, DataflowSpec, DataflowStats};
+
+/// Schedules the worklist algorithm over the condensation DAG of strongly
+/// connected components. Each component is solved to a fixpoint once all of
+/// its predecessor components are done, so blocks outside of loops are only
+/// processed once.
+pub struct SccExecutor {
+    /// Whether to solve independent components at the same topological level
+    /// in parallel
+    parallel: bool,
+}
+
+impl SccExecutor {
+    /// Create a new SccExecutor
+    pub fn new(parallel: bool) -> Self {
+        Self { parallel }
+    }
+}
+
+/// Solve a single component to a fixpoint. All predecessor components must
+/// already be solved. Returns the in and out values of the component's blocks,
+/// in the same order as `comp`, along with the work done.
+fn solve<Pass>(
+    pass: &Pass,
+    cfg: &CFG,
+    transfer: &BlockTransfer<Pass>,
+    comp: &[usize],
+    comp_of: &[usize],
+    pos: &[usize],
+    out_vals: &[Pass::Val],
+) -> (Vec<(Pass::Val, Pass::Val)>, DataflowStats)
+where
+    Pass: DataflowSpec,
+{
+    let c = comp_of[comp[0]];
+    let mut vals = comp
+        .iter()
+        .map(|&i| (out_vals[i].clone(), out_vals[i].clone()))
+        .collect_vec();
+
+    let mut worklist: VecDeque<_> = (0..comp.len()).collect();
+    let mut pending = vec![true; comp.len()];
+    let mut stats = DataflowStats {
+        pushes: comp.len(),
+        ..Default::default()
+    };
+
+    while let Some(k) = worklist.pop_front() {
+        stats.observe_width(worklist.len() + 1);
+        pending[k] = false;
+        let i = comp[k];
+
+        let in_val = if cfg.func().get(i).is_entry() {
+            pass.entry(cfg.func())
+        } else {
+            let preds = cfg.preds(i).iter().copied().collect_vec();
+            let inputs = preds
+                .iter()
+                .map(|&j| {
+                    let val = if comp_of[j] == c {
+                        vals[pos[j]].1.clone()
+                    } else {
+                        out_vals[j].clone()
+                    };
+                    pass.edge_transfer(cfg.func(), j, i, val)
+                })
+                .collect_vec();
+            stats.meets += 1;
+            pass.meet_edges(cfg.func(), i, &preds, &inputs)
+        };
+
+        let new_vals = transfer.apply(i, &in_val, &vals[k].1);
+        stats.transfers += 1;
+        vals[k].0 = in_val;
+
+        if new_vals != vals[k].1 {
+            log::trace!("New values for block {}: {:?}", i, new_vals);
+            vals[k].1 = new_vals;
+            for j in cfg.succs(i) {
+                if comp_of[j] == c && !pending[pos[j]] {
+                    pending[pos[j]] = true;
+                    worklist.push_back(pos[j]);
+                    stats.pushes += 1;
+                }
+            }
+        }
+    }
+
+    (vals, stats)
+}
+
+impl<Pass> DataflowExecutor<Pass> for SccExecutor
+where
+    Pass: DataflowSpec + Send + Sync,
+{
+    fn cfg(&self, pass: &Pass, cfg: CFG) -> (Dataflow<Pass::Val>, DataflowStats) {
+        log::debug!("Function {}", cfg.name());
+        let cfg = if cfg.reversed() != pass.reversed() {
+            cfg.reverse()
+        } else {
+            cfg
+        };
+
+        let n = cfg.len();
+
+        let mut in_vals = vec![pass.init(cfg.func()); n];
+        let mut out_vals = vec![pass.init(cfg.func()); n];
+        let transfer = BlockTransfer::new(pass, &cfg);
+
+        let (mut comps, comp_of) = strongly_connected_components(&cfg);
+
+        // Process the blocks of each component in reverse postorder, so that
+        // loop headers come first
+        let mut rank = vec![0; n];
+        for (r, i) in reverse_postorder(&cfg).into_iter().enumerate() {
+            rank[i] = r;
+        }
+        let mut pos = vec![0; n];
+        for comp in comps.iter_mut() {
+            comp.sort_by_key(|&i| rank[i]);
+            for (k, &i) in comp.iter().enumerate() {
+                pos[i] = k;
+            }
+        }
+
+        // Group components by their depth in the condensation DAG. Components
+        // at the same depth never depend on each other.
+        let mut depth = vec![0; comps.len()];
+        for (c, comp) in comps.iter().enumerate() {
+            for &i in comp {
+                for &j in cfg.preds(i).iter() {
+                    if comp_of[j] != c {
+                        depth[c] = depth[c].max(depth[comp_of[j]] + 1);
+                    }
+                }
+            }
+        }
+        let mut levels = vec![Vec::new(); depth.iter().max().map_or(0, |d| d + 1)];
+        for (c, d) in depth.into_iter().enumerate() {
+            levels[d].push(c);
+        }
+
+        let mut stats = DataflowStats::default();
+        for level in levels {
+            log::trace!("Level: {:?}", level);
+            stats.rounds += 1;
+            let solve_comp =
+                |&c: &usize| solve(pass, &cfg, &transfer, &comps[c], &comp_of, &pos, &out_vals);
+            let results: Vec<_> = if self.parallel {
+                level.par_iter().map(solve_comp).collect()
+            } else {
+                level.iter().map(solve_comp).collect()
+            };
+
+            // All components of a level are pending at once when solved in parallel
+            if self.parallel {
+                stats.observe_width(level.iter().map(|&c| comps[c].len()).sum());
+            }
+
+            for (c, (vals, comp_stats)) in level.into_iter().zip(results) {
+                stats = [stats, comp_stats].into_iter().sum();
+                for (&i, (i_vals, o_vals)) in comps[c].iter().zip(vals) {
+                    in_vals[i] = i_vals;
+                    out_vals[i] = o_vals;
+                }
+            }
+        }
+
+        // The exit value can be computed by meeting all the out values of exit block(s)
+        let exit_val = cfg
+            .exits()
+            .into_iter()
+            .map(|i| out_vals[i].clone())
+            .collect_vec();
+        let exit_val = pass.meet(&exit_val);
+        let exit_val = pass.finish(cfg.func(), exit_val);
+        stats.reprocessed = stats.transfers.saturating_sub(n);
+
+        (
+            Dataflow {
+                cfg,
+                in_vals,
+                out_vals,
+                exit_val,
+            },
+            stats,
+        )
+    }
+}
@@ -0,0 +1,107 @@
+use std::sync::atomic::{AtomicU8, AtomicUsize, Ordering};
+use utils::DataflowStats;
+
+/// Not scheduled
+const IDLE: u8 = 0;
+/// Spawned, but not yet started
+const QUEUED: u8 = 1;
+/// Currently being processed by a thread
+const RUNNING: u8 = 2;
+/// Being processed, and an input changed after processing started
+const DIRTY: u8 = 3;
+
+#[derive(Default)]
+/// Lock-free scheduling state of a unit of work (e.g. a basic block) shared
+/// between threads. At most one thread processes a unit at a time, and requests
+/// that arrive while it is being processed are never lost.
+pub(crate) struct TaskState(AtomicU8);
+
+impl TaskState {
+    /// Request that the unit be (re)processed. Returns true if the caller is
+    /// responsible for spawning a new task for it.
+    pub(crate) fn schedule(&self) -> bool {
+        let mut state = self.0.load(Ordering::Acquire);
+        loop {
+            let next = match state {
+                IDLE => QUEUED,
+                RUNNING => DIRTY,
+                // Already queued or already marked dirty
+                _ => return false,
+            };
+
+            match self
+                .0
+                .compare_exchange_weak(state, next, Ordering::AcqRel, Ordering::Acquire)
+            {
+                Ok(_) => return next == QUEUED,
+                Err(actual) => state = actual,
+            }
+        }
+    }
+
+    /// Mark a queued unit as being processed
+    pub(crate) fn start(&self) {
+        self.0.store(RUNNING, Ordering::Release);
+    }
+
+    /// Try to finish processing the unit. Returns false if it was rescheduled
+    /// in the meantime, in which case the caller must process it again.
+    pub(crate) fn finish(&self) -> bool {
+        match self
+            .0
+            .compare_exchange(RUNNING, IDLE, Ordering::AcqRel, Ordering::Acquire)
+        {
+            Ok(_) => true,
+            Err(_) => {
+                self.0.store(RUNNING, Ordering::Release);
+                false
+            }
+        }
+    }
+}
+
+#[derive(Default)]
+/// Work counters shared between concurrently running tasks
+pub(crate) struct SharedStats {
+    transfers: AtomicUsize,
+    meets: AtomicUsize,
+    pushes: AtomicUsize,
+    width: AtomicUsize,
+    max_width: AtomicUsize,
+}
+
+impl SharedStats {
+    pub(crate) fn transfer(&self) {
+        self.transfers.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub(crate) fn meet(&self) {
+        self.meets.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Record that `count` blocks became pending
+    pub(crate) fn push(&self, count: usize) {
+        self.pushes.fetch_add(count, Ordering::Relaxed);
+        let width = self.width.fetch_add(count, Ordering::Relaxed) + count;
+        self.max_width.fetch_max(width, Ordering::Relaxed);
+    }
+
+    /// Record that a pending block is no longer pending
+    pub(crate) fn pop(&self) {
+        self.width.fetch_sub(1, Ordering::Relaxed);
+    }
+
+    /// Collect the final counters for a CFG with `n` blocks
+    pub(crate) fn into_stats(self, n: usize) -> DataflowStats {
+        let transfers = self.transfers.into_inner();
+        DataflowStats {
+            transfers,
+            meets: self.meets.into_inner(),
+            pushes: self.pushes.into_inner(),
+            rounds: 0,
+            max_width: self.max_width.into_inner(),
+            reprocessed: transfers.saturating_sub(n),
+            batches: Vec::new(),
+        }
+    }
+}
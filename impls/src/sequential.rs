@@ -1,7 +1,8 @@
+use crate::graph::reverse_postorder;
 use bril_utils::{CFG, Dataflow};
 use itertools::Itertools;
-use std::collections::LinkedList;
-use utils::{DataflowExecutor, DataflowSpec};
+use std::collections::{BTreeSet, LinkedList};
+use utils::{BlockTransfer, DataflowExecutor, DataflowSpec, DataflowStats};
 
 #[derive(Default)]
 pub struct SequentialExecutor;
@@ -10,7 +11,7 @@ impl<Pass> DataflowExecutor<Pass> for SequentialExecutor
 where
     Pass: DataflowSpec + Send + Sync,
 {
-    fn cfg(&self, pass: &Pass, cfg: CFG) -> Dataflow<Pass::Val> {
+    fn cfg(&self, pass: &Pass, cfg: CFG) -> (Dataflow<Pass::Val>, DataflowStats) {
         log::debug!("Function {}", cfg.name());
         let cfg = if cfg.reversed() != pass.reversed() {
             cfg.reverse()
@@ -22,28 +23,38 @@ where
 
         let mut in_vals = vec![pass.init(cfg.func()); n];
         let mut out_vals = vec![pass.init(cfg.func()); n];
+        let transfer = BlockTransfer::new(pass, &cfg);
+
+        let mut stats = DataflowStats {
+            pushes: n,
+            ..Default::default()
+        };
 
         let mut worklist: LinkedList<_> = (0..n).collect();
         while let Some(i) = worklist.pop_front() {
             log::trace!("Worklist: {:?}", worklist);
+            stats.observe_width(worklist.len() + 1);
             in_vals[i] = if cfg.func().get(i).is_entry() {
                 pass.entry(cfg.func())
             } else {
-                let inputs = cfg
-                    .preds(i)
+                let preds = cfg.preds(i).iter().copied().collect_vec();
+                let inputs = preds
                     .iter()
-                    .map(|&j| out_vals[j].clone())
+                    .map(|&j| pass.edge_transfer(cfg.func(), j, i, out_vals[j].clone()))
                     .collect_vec();
-                pass.meet(&inputs)
+                stats.meets += 1;
+                pass.meet_edges(cfg.func(), i, &preds, &inputs)
             };
 
-            let new_vals = pass.transfer(cfg.func().get(i), &in_vals[i]);
+            let new_vals = transfer.apply(i, &in_vals[i], &out_vals[i]);
+            stats.transfers += 1;
 
             if new_vals != out_vals[i] {
                 log::trace!("New values for block {}: {:?}", i, new_vals);
                 out_vals[i] = new_vals;
                 for j in cfg.succs(i) {
                     worklist.push_back(j);
+                    stats.pushes += 1;
                 }
             }
         }
@@ -56,12 +67,105 @@ where
             .collect_vec();
         let exit_val = pass.meet(&exit_val);
         let exit_val = pass.finish(cfg.func(), exit_val);
+        stats.reprocessed = stats.transfers.saturating_sub(n);
+
+        (
+            Dataflow {
+                cfg,
+                in_vals,
+                out_vals,
+                exit_val,
+            },
+            stats,
+        )
+    }
+}
+
+#[derive(Default)]
+/// Sequential worklist algorithm that always picks the pending block that comes
+/// first in reverse postorder (postorder for reversed passes), and never holds
+/// the same block twice
+pub struct RpoSequentialExecutor;
+
+impl<Pass> DataflowExecutor<Pass> for RpoSequentialExecutor
+where
+    Pass: DataflowSpec + Send + Sync,
+{
+    fn cfg(&self, pass: &Pass, cfg: CFG) -> (Dataflow<Pass::Val>, DataflowStats) {
+        log::debug!("Function {}", cfg.name());
+        let cfg = if cfg.reversed() != pass.reversed() {
+            cfg.reverse()
+        } else {
+            cfg
+        };
 
-        Dataflow {
-            cfg,
-            in_vals,
-            out_vals,
-            exit_val,
+        let n = cfg.len();
+
+        let mut in_vals = vec![pass.init(cfg.func()); n];
+        let mut out_vals = vec![pass.init(cfg.func()); n];
+        let transfer = BlockTransfer::new(pass, &cfg);
+
+        // The worklist holds positions in the order rather than block indices
+        let order = reverse_postorder(&cfg);
+        let mut rank = vec![0; n];
+        for (r, &i) in order.iter().enumerate() {
+            rank[i] = r;
         }
+
+        let mut stats = DataflowStats {
+            pushes: n,
+            ..Default::default()
+        };
+
+        let mut worklist: BTreeSet<_> = (0..n).collect();
+        while let Some(r) = worklist.pop_first() {
+            log::trace!("Worklist: {:?}", worklist);
+            stats.observe_width(worklist.len() + 1);
+            let i = order[r];
+            in_vals[i] = if cfg.func().get(i).is_entry() {
+                pass.entry(cfg.func())
+            } else {
+                let preds = cfg.preds(i).iter().copied().collect_vec();
+                let inputs = preds
+                    .iter()
+                    .map(|&j| pass.edge_transfer(cfg.func(), j, i, out_vals[j].clone()))
+                    .collect_vec();
+                stats.meets += 1;
+                pass.meet_edges(cfg.func(), i, &preds, &inputs)
+            };
+
+            let new_vals = transfer.apply(i, &in_vals[i], &out_vals[i]);
+            stats.transfers += 1;
+
+            if new_vals != out_vals[i] {
+                log::trace!("New values for block {}: {:?}", i, new_vals);
+                out_vals[i] = new_vals;
+                for j in cfg.succs(i) {
+                    if worklist.insert(rank[j]) {
+                        stats.pushes += 1;
+                    }
+                }
+            }
+        }
+
+        // The exit value can be computed by meeting all the out values of exit block(s)
+        let exit_val = cfg
+            .exits()
+            .into_iter()
+            .map(|i| out_vals[i].clone())
+            .collect_vec();
+        let exit_val = pass.meet(&exit_val);
+        let exit_val = pass.finish(cfg.func(), exit_val);
+        stats.reprocessed = stats.transfers.saturating_sub(n);
+
+        (
+            Dataflow {
+                cfg,
+                in_vals,
+                out_vals,
+                exit_val,
+            },
+            stats,
+        )
     }
 }
@@ -0,0 +1,296 @@
+use crate::{Pass, SequentialExecutor};
+use bril_utils::{BBFunction, Dataflow, HashableLiteral, InstrExt, bril_rs::Literal};
+use passes::{
+    ConstProp, IntDomain, IntState, IntervalAnalysis, Intervals, LiveVars, Parity, ParityVal,
+    ReachingDefinition, ReachingDefs, Sccp, SccpVal, Sign, SignVal, Value,
+};
+use std::{
+    collections::{HashMap, HashSet},
+    fmt::Display,
+};
+use utils::{
+    DataflowExecutor, DataflowSpec, Env, Interpreter, Observer, bench_args, block_label,
+    load_program,
+};
+
+/// A fact of a pass that does not hold in a concrete run
+pub struct Violation {
+    pub pass: Pass,
+    pub func: String,
+    pub block: usize,
+    pub label: Option<String>,
+    pub message: String,
+}
+
+impl Display for Violation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:?} @{}: ", self.pass, self.func)?;
+        match &self.label {
+            Some(label) => write!(f, ".{}", label)?,
+            None => write!(f, "block {}", self.block)?,
+        }
+        write!(f, ": {}", self.message)
+    }
+}
+
+/// What a single instruction or block end did, in the order it happened
+enum Event {
+    Read(String),
+    Write(String),
+    /// The end of a block
+    End(usize),
+}
+
+/// The state of a single call
+struct Frame {
+    /// The definition each variable currently holds
+    defs: HashMap<String, ReachingDefinition>,
+    events: Vec<Event>,
+}
+
+/// Static results of a pass, by function name
+fn solve<Spec: DataflowSpec + Send + Sync>(
+    pass: &Spec,
+    source: &str,
+) -> HashMap<String, Dataflow<Spec::Val>> {
+    let (_, results) = SequentialExecutor.run(pass, source.as_bytes(), false);
+    results
+        .into_iter()
+        .map(|d| (d.cfg.name().to_string(), d))
+        .collect()
+}
+
+/// Checks the facts of each pass against the concrete states of a run
+struct Checker {
+    live: HashMap<String, Dataflow<HashSet<String>>>,
+    consts: HashMap<String, Dataflow<HashMap<String, Value>>>,
+    defs: HashMap<String, Dataflow<HashSet<ReachingDefinition>>>,
+    sccp: HashMap<String, Dataflow<SccpVal>>,
+    intervals: HashMap<String, Dataflow<Intervals>>,
+    signs: HashMap<String, Dataflow<IntState<SignVal>>>,
+    parities: HashMap<String, Dataflow<IntState<ParityVal>>>,
+    frames: Vec<Frame>,
+    violations: Vec<Violation>,
+}
+
+/// The value of a pass at the start or end of a block
+fn at<Val>(dataflow: &Dataflow<Val>, block: usize, exit: bool) -> &Val {
+    if exit {
+        &dataflow.out_vals[block]
+    } else {
+        &dataflow.in_vals[block]
+    }
+}
+
+/// The integer value of a variable in a concrete state, if it has one
+fn int(env: &Env, name: &str) -> Option<i64> {
+    match env.get(name) {
+        Some(Literal::Int(a)) => Some(*a),
+        _ => None,
+    }
+}
+
+/// The constants that do not hold in a concrete state
+fn check_consts(pass: Pass, consts: &HashMap<String, Value>, env: &Env) -> Vec<(Pass, String)> {
+    consts
+        .iter()
+        .filter_map(|(name, val)| match (val, env.get(name)) {
+            (Value::Const(c), Some(actual)) if *c != HashableLiteral::from(actual.clone()) => {
+                Some((pass, format!("{} is {}, not {}", name, actual, c)))
+            }
+            // Not a constant, or not defined yet
+            _ => None,
+        })
+        .collect()
+}
+
+/// The abstract integers that do not hold in a concrete state
+fn check_ints<D: IntDomain>(pass: Pass, state: &IntState<D>, env: &Env) -> Vec<(Pass, String)> {
+    state
+        .iter()
+        .filter_map(|(name, val)| match int(env, name) {
+            Some(a) if !val.contains(a) => {
+                Some((pass, format!("{} is {}, not in {:?}", name, a, val)))
+            }
+            _ => None,
+        })
+        .collect()
+}
+
+impl Checker {
+    fn report(&mut self, pass: Pass, func: &BBFunction, block: usize, message: String) {
+        self.violations.push(Violation {
+            pass,
+            func: func.name.clone(),
+            block,
+            label: block_label(func.get(block)).map(str::to_string),
+            message,
+        });
+    }
+
+    /// The facts of the forward passes that do not hold at the start or end of
+    /// a block in a call
+    fn check_state(&self, func: &str, block: usize, exit: bool, env: &Env) -> Vec<(Pass, String)> {
+        let frame = self.frames.last().unwrap();
+        let mut violations =
+            check_consts(Pass::ConstProp, at(&self.consts[func], block, exit), env);
+
+        let defs = at(&self.defs[func], block, exit);
+        for def in frame.defs.values() {
+            if !defs.contains(def) {
+                let message = format!("{:?} reaches but is not in {:?}", def, defs);
+                violations.push((Pass::ReachingDefinitions, message));
+            }
+        }
+
+        match at(&self.sccp[func], block, exit) {
+            SccpVal::Unreachable => {
+                let message = "block is reached but found unreachable".to_string();
+                violations.push((Pass::Sccp, message));
+            }
+            SccpVal::Reachable(consts) => violations.extend(check_consts(Pass::Sccp, consts, env)),
+        }
+
+        for (name, range) in at(&self.intervals[func], block, exit).iter() {
+            match int(env, name) {
+                Some(a) if !range.contains(a) => {
+                    let message = format!("{} is {}, not in {:?}", name, a, range);
+                    violations.push((Pass::Intervals, message));
+                }
+                _ => {}
+            }
+        }
+
+        violations.extend(check_ints(
+            Pass::Sign,
+            at(&self.signs[func], block, exit),
+            env,
+        ));
+        violations.extend(check_ints(
+            Pass::Parity,
+            at(&self.parities[func], block, exit),
+            env,
+        ));
+
+        violations
+    }
+}
+
+impl Observer for Checker {
+    fn call(&mut self, func: &BBFunction) {
+        self.frames.push(Frame {
+            defs: func
+                .args
+                .iter()
+                .map(|arg| {
+                    (
+                        arg.name.clone(),
+                        ReachingDefinition::entry(arg.name.clone()),
+                    )
+                })
+                .collect(),
+            events: Vec::new(),
+        });
+    }
+
+    fn enter(&mut self, func: &BBFunction, block: usize, env: &Env) {
+        let violations = self.check_state(&func.name, block, false, env);
+        for (pass, message) in violations {
+            self.report(pass, func, block, message);
+        }
+    }
+
+    fn instr(&mut self, func: &BBFunction, block: usize, instr: usize, _env: &Env) {
+        let insn = func.get(block).iter().nth(instr).unwrap();
+        let frame = self.frames.last_mut().unwrap();
+
+        for arg in insn.args().into_iter().flatten() {
+            frame.events.push(Event::Read(arg));
+        }
+        if let Some(dest) = insn.dest() {
+            frame.events.push(Event::Write(dest.clone()));
+            frame.defs.insert(
+                dest.clone(),
+                ReachingDefinition {
+                    name: dest,
+                    block,
+                    instr: Some(instr),
+                },
+            );
+        }
+    }
+
+    fn exit(&mut self, func: &BBFunction, block: usize, env: &Env) {
+        let violations = self.check_state(&func.name, block, true, env);
+        for (pass, message) in violations {
+            self.report(pass, func, block, message);
+        }
+        self.frames
+            .last_mut()
+            .unwrap()
+            .events
+            .push(Event::End(block));
+    }
+
+    fn ret(&mut self, func: &BBFunction) {
+        let frame = self.frames.pop().unwrap();
+
+        // Walk the call backwards to find the variables that are read before
+        // being written again after the end of each block. For a reversed pass
+        // the in value of a block holds at its end.
+        let mut live = HashSet::new();
+        let mut violations = Vec::new();
+        for event in frame.events.into_iter().rev() {
+            match event {
+                Event::Read(name) => {
+                    live.insert(name);
+                }
+                Event::Write(name) => {
+                    live.remove(&name);
+                }
+                Event::End(block) => {
+                    let facts = &self.live[&func.name].in_vals[block];
+                    let mut missing: Vec<_> = live.difference(facts).cloned().collect();
+                    missing.sort();
+                    if !missing.is_empty() {
+                        violations.push((block, missing));
+                    }
+                }
+            }
+        }
+
+        for (block, missing) in violations {
+            let message = format!("{:?} are read later but not live", missing);
+            self.report(Pass::LiveVariables, func, block, message);
+        }
+    }
+}
+
+/// Number of instructions a checked run may execute. Every block is checked on
+/// entry and exit, so runs are cut much shorter than plain interpretation.
+const STEP_LIMIT: usize = 1_000_000;
+
+/// Run a program in Bril text form on the arguments of its `# ARGS:` comment,
+/// and check that the facts of live variables, constant propagation, reaching
+/// definitions, SCCP, intervals, sign and parity hold at the start and end of
+/// every block the run goes through. Returns an error if the program itself
+/// fails or runs more than `STEP_LIMIT` instructions.
+pub fn check_soundness(source: &str) -> Result<Vec<Violation>, String> {
+    let mut checker = Checker {
+        live: solve(&LiveVars, source),
+        consts: solve(&ConstProp, source),
+        defs: solve(&ReachingDefs, source),
+        sccp: solve(&Sccp, source),
+        intervals: solve(&IntervalAnalysis::default(), source),
+        signs: solve(&Sign, source),
+        parities: solve(&Parity, source),
+        frames: Vec::new(),
+        violations: Vec::new(),
+    };
+
+    let prog = load_program(source.as_bytes());
+    Interpreter::new(&prog)
+        .with_limit(STEP_LIMIT)
+        .run(&bench_args(source), &mut checker)?;
+    Ok(checker.violations)
+}
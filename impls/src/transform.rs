@@ -0,0 +1,78 @@
+use crate::Executor;
+use bril_utils::{
+    CFG,
+    bril_rs::{Function, Program},
+};
+use passes::{AvailableExpr, ConstProp, LiveVars, common_subexprs, dead_code, fold_constants};
+use strum::{Display, EnumIter, EnumString};
+use utils::{DataflowExecutor, bril_function, load_program};
+
+#[derive(EnumString, EnumIter, Debug, Display, Clone, Copy, PartialEq, Eq)]
+/// Program transformations driven by the results of a pass
+pub enum Transform {
+    /// Dead code elimination, using live variables
+    #[strum(serialize = "dce", serialize = "dead-code")]
+    Dce,
+    /// Common subexpression elimination, using available expressions
+    #[strum(serialize = "cse", serialize = "common-subexprs")]
+    Cse,
+    /// Constant folding and propagation, using constant propagation
+    #[strum(serialize = "const-fold", serialize = "constant-folding")]
+    ConstFold,
+}
+
+/// Delete dead pure instructions from a function until none are left. Every
+/// deletion can make more variables dead, so liveness is recomputed each time.
+fn dce<E: DataflowExecutor<LiveVars>>(executor: &E, mut func: Function) -> Function {
+    loop {
+        let (dataflow, _) = executor.cfg(&LiveVars, CFG::from(func.clone()));
+        match dead_code(&dataflow) {
+            Some(instrs) => func = bril_function(dataflow.cfg.func(), instrs),
+            None => return func,
+        }
+    }
+}
+
+/// Replace recomputed expressions of a function by copies. Copies never make
+/// other expressions available, so a single pass is enough.
+fn cse<E: DataflowExecutor<AvailableExpr>>(executor: &E, func: Function) -> Function {
+    let (dataflow, _) = executor.cfg(&AvailableExpr, CFG::from(func.clone()));
+    match common_subexprs(&dataflow) {
+        Some(instrs) => bril_function(dataflow.cfg.func(), instrs),
+        None => func,
+    }
+}
+
+/// Fold constants and branches of a function until nothing changes. Folding a
+/// branch removes an edge from the CFG, which can make more values constant.
+fn const_fold<E: DataflowExecutor<ConstProp>>(executor: &E, mut func: Function) -> Function {
+    loop {
+        let (dataflow, _) = executor.cfg(&ConstProp, CFG::from(func.clone()));
+        match fold_constants(&dataflow) {
+            Some(instrs) => func = bril_function(dataflow.cfg.func(), instrs),
+            None => return func,
+        }
+    }
+}
+
+impl Transform {
+    /// Apply the transformation to every function of a program
+    pub fn apply(&self, executor: &Executor, mut prog: Program) -> Program {
+        prog.functions = std::mem::take(&mut prog.functions)
+            .into_iter()
+            .map(|func| match self {
+                Transform::Dce => crate::with_executor!(executor, dce(func)),
+                Transform::Cse => crate::with_executor!(executor, cse(func)),
+                Transform::ConstFold => crate::with_executor!(executor, const_fold(func)),
+            })
+            .collect();
+        prog
+    }
+
+    /// Apply the transformation to a program in Bril text form, returning the
+    /// rewritten program as Bril JSON
+    pub fn execute<R: std::io::Read>(&self, executor: &Executor, input: R) -> String {
+        let prog = self.apply(executor, load_program(input));
+        serde_json::to_string_pretty(&prog).unwrap()
+    }
+}
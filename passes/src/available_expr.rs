@@ -1,13 +1,15 @@
+use crate::bitset::{BitSet, Universe, UniverseCache};
 use bril_utils::{
     BBFunction, BasicBlock, HashableLiteral, InstrExt,
     bril_rs::{Instruction, ValueOps},
 };
 use itertools::Itertools;
+use serde::Serialize;
 use std::{
-    collections::HashSet,
+    collections::{HashMap, HashSet},
     fmt::{Debug, Display},
 };
-use utils::DataflowSpec;
+use utils::{DataflowSpec, GenKill, GenKillSpec};
 
 #[derive(Clone, PartialEq, Eq, Hash)]
 pub enum Expr {
@@ -18,7 +20,7 @@ pub enum Expr {
 }
 
 impl Expr {
-    fn contains(&self, arg: &str) -> bool {
+    pub(crate) fn contains(&self, arg: &str) -> bool {
         match self {
             Expr::Const(_) => false,
             Expr::Op(_, args) => args.contains(&arg.to_string()),
@@ -27,7 +29,7 @@ impl Expr {
 }
 
 impl Expr {
-    fn new(instr: &Instruction) -> Option<Self> {
+    pub(crate) fn new(instr: &Instruction) -> Option<Self> {
         if instr.is_pure() {
             match instr {
                 Instruction::Value { args, op, .. } => Some(Expr::Op(*op, args.clone())),
@@ -49,6 +51,15 @@ impl Display for Expr {
     }
 }
 
+impl Serialize for Expr {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.collect_str(self)
+    }
+}
+
 #[derive(Clone, PartialEq, Eq)]
 pub enum Set {
     /// Set of all items
@@ -58,15 +69,15 @@ pub enum Set {
 }
 
 impl Set {
-    fn empty() -> Self {
+    pub(crate) fn empty() -> Self {
         Set::Finite(HashSet::new())
     }
 
-    fn full() -> Self {
+    pub(crate) fn full() -> Self {
         Set::Full
     }
 
-    fn intersect(&self, other: &Self) -> Self {
+    pub(crate) fn intersect(&self, other: &Self) -> Self {
         match (self, other) {
             (Set::Full, _) => other.clone(),
             (_, Set::Full) => self.clone(),
@@ -74,7 +85,7 @@ impl Set {
         }
     }
 
-    fn insert(&mut self, expr: Expr) {
+    pub(crate) fn insert(&mut self, expr: Expr) {
         match self {
             Set::Full => {}
             Set::Finite(set) => {
@@ -82,6 +93,13 @@ impl Set {
             }
         }
     }
+
+    pub fn contains(&self, expr: &Expr) -> bool {
+        match self {
+            Set::Full => true,
+            Set::Finite(set) => set.contains(expr),
+        }
+    }
 }
 
 impl Debug for Set {
@@ -95,6 +113,33 @@ impl Debug for Set {
     }
 }
 
+impl Serialize for Set {
+    /// The full set is serialized as "T", like its debug representation
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        match self {
+            Set::Full => serializer.serialize_str("T"),
+            Set::Finite(set) => serializer.collect_seq(set),
+        }
+    }
+}
+
+/// Update the expressions available at a point after `instr`
+pub(crate) fn make_available(set: &mut Set, instr: &Instruction) {
+    if let Some(e) = Expr::new(instr) {
+        set.insert(e);
+    }
+
+    if let Some(dest) = instr.dest() {
+        // Remove all expressions that contain the destination
+        if let Set::Finite(set) = set {
+            set.retain(|expr| !expr.contains(&dest));
+        }
+    }
+}
+
 #[derive(Default)]
 pub struct AvailableExpr;
 
@@ -119,19 +164,173 @@ impl DataflowSpec for AvailableExpr {
     fn transfer(&self, block: &BasicBlock, in_val: &Set) -> Set {
         let mut out_vals = in_val.clone();
 
+        for instr in block.iter() {
+            make_available(&mut out_vals, instr);
+        }
+
+        out_vals
+    }
+
+    fn as_gen_kill(&self) -> Option<&dyn GenKillSpec<Val = Self::Val>> {
+        Some(self)
+    }
+}
+
+impl GenKillSpec for AvailableExpr {
+    fn gen_kill(&self, func: &BBFunction) -> Vec<GenKill<Set>> {
+        // Every expression in the function, by the variables it uses
+        let mut uses: HashMap<String, HashSet<Expr>> = HashMap::new();
+        for expr in func
+            .blocks
+            .iter()
+            .flat_map(|block| block.iter())
+            .filter_map(Expr::new)
+        {
+            if let Expr::Op(_, args) = &expr {
+                for arg in args {
+                    uses.entry(arg.clone()).or_default().insert(expr.clone());
+                }
+            }
+        }
+
+        func.blocks
+            .iter()
+            .map(|block| {
+                // Expressions still available at the end of the block are
+                // generated
+                let gen_set = self.transfer(block, &Set::empty());
+
+                // Every expression that uses a variable defined in this block
+                // is killed
+                let kill_set = Set::Finite(
+                    block
+                        .iter()
+                        .filter_map(|instr| instr.dest())
+                        .filter_map(|dest| uses.get(&dest))
+                        .flatten()
+                        .cloned()
+                        .collect(),
+                );
+
+                GenKill { gen_set, kill_set }
+            })
+            .collect()
+    }
+
+    fn apply(&self, gen_kill: &GenKill<Set>, val: &mut Set) {
+        match (&mut *val, &gen_kill.gen_set, &gen_kill.kill_set) {
+            (Set::Finite(set), Set::Finite(gen_set), Set::Finite(kill_set)) => {
+                set.retain(|expr| !kill_set.contains(expr));
+                set.extend(gen_set.iter().cloned());
+            }
+            // Nothing is removed from the full set
+            _ => *val = Set::Full,
+        }
+    }
+}
+
+#[derive(Clone, PartialEq, Eq)]
+/// Set of expressions stored as a bit vector over the function's expressions
+pub enum DenseSet {
+    /// Set of all items
+    Full,
+    /// Set of finite items
+    Finite(BitSet<Expr>),
+}
+
+impl PartialEq<Set> for DenseSet {
+    fn eq(&self, other: &Set) -> bool {
+        match (self, other) {
+            (DenseSet::Full, Set::Full) => true,
+            (DenseSet::Finite(a), Set::Finite(b)) => a == b,
+            _ => false,
+        }
+    }
+}
+
+impl Debug for DenseSet {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DenseSet::Full => Display::fmt("T", f),
+            DenseSet::Finite(set) => {
+                write!(f, "{{{}}}", set.iter().map(|e| format!("{}", e)).join(", "))
+            }
+        }
+    }
+}
+
+impl Serialize for DenseSet {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        match self {
+            DenseSet::Full => serializer.serialize_str("T"),
+            DenseSet::Finite(set) => set.serialize(serializer),
+        }
+    }
+}
+
+#[derive(Default)]
+/// Available expressions, with sets stored as bit vectors over the function's
+/// expressions
+pub struct AvailableExprBv {
+    universes: UniverseCache<Expr>,
+}
+
+impl DataflowSpec for AvailableExprBv {
+    type Val = DenseSet;
+
+    fn entry(&self, func: &BBFunction) -> DenseSet {
+        // Entry block is empty
+        DenseSet::Finite(BitSet::empty(self.universes.get(func, || {
+            Universe::new(
+                func.blocks
+                    .iter()
+                    .flat_map(|block| block.iter())
+                    .filter_map(Expr::new),
+            )
+        })))
+    }
+
+    fn init(&self, _: &BBFunction) -> DenseSet {
+        DenseSet::Full
+    }
+
+    fn meet(&self, in_vals: &[DenseSet]) -> DenseSet {
+        // Set intersection, ignoring full sets
+        let finite = in_vals
+            .iter()
+            .filter_map(|val| match val {
+                DenseSet::Full => None,
+                DenseSet::Finite(set) => Some(set.clone()),
+            })
+            .collect_vec();
+
+        if finite.is_empty() {
+            DenseSet::Full
+        } else {
+            DenseSet::Finite(BitSet::intersection(&finite))
+        }
+    }
+
+    fn transfer(&self, block: &BasicBlock, in_val: &DenseSet) -> DenseSet {
+        let DenseSet::Finite(in_set) = in_val else {
+            return DenseSet::Full;
+        };
+        let mut out_set = in_set.clone();
+
         for instr in block.iter() {
             if let Some(e) = Expr::new(instr) {
-                out_vals.insert(e);
+                out_set.insert(e);
             }
 
             if let Some(dest) = instr.dest() {
                 // Remove all expressions that contain the destination
-                if let Set::Finite(set) = &mut out_vals {
-                    set.retain(|expr| !expr.contains(&dest));
-                }
+                out_set.retain(|expr| !expr.contains(&dest));
             }
         }
 
-        out_vals
+        DenseSet::Finite(out_set)
     }
 }
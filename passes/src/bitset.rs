@@ -0,0 +1,259 @@
+use bril_utils::BBFunction;
+use itertools::Either;
+use serde::Serialize;
+use std::{
+    borrow::Cow,
+    collections::{HashMap, HashSet},
+    fmt::Debug,
+    hash::Hash,
+    sync::{Arc, RwLock},
+};
+
+const BITS: usize = u64::BITS as usize;
+
+fn bit(words: &[u64], i: usize) -> bool {
+    (words[i / BITS] >> (i % BITS)) & 1 == 1
+}
+
+/// Dense numbering of the items a set-based pass reasons about in a function
+pub struct Universe<T> {
+    items: Vec<T>,
+    index: HashMap<T, usize>,
+}
+
+impl<T: Clone + Eq + Hash> Universe<T> {
+    /// Number the given items in order, ignoring duplicates
+    pub fn new(items: impl IntoIterator<Item = T>) -> Self {
+        let mut universe = Self {
+            items: Vec::new(),
+            index: HashMap::new(),
+        };
+
+        for item in items {
+            if !universe.index.contains_key(&item) {
+                universe.index.insert(item.clone(), universe.items.len());
+                universe.items.push(item);
+            }
+        }
+
+        universe
+    }
+
+    /// Number of words needed to store a set over this universe
+    fn words(&self) -> usize {
+        self.items.len().div_ceil(BITS)
+    }
+}
+
+/// Universes of every function seen by a pass, so that all values computed for
+/// the same function share one numbering. A universe is only built the first
+/// time its function is seen, and later lookups share a read lock. Functions
+/// are told apart by name, so a pass is meant for a single program.
+pub struct UniverseCache<T>(RwLock<HashMap<String, Arc<Universe<T>>>>);
+
+impl<T> Default for UniverseCache<T> {
+    fn default() -> Self {
+        Self(RwLock::new(HashMap::new()))
+    }
+}
+
+impl<T> UniverseCache<T> {
+    /// Get the universe of a function, building it on first use
+    pub fn get(&self, func: &BBFunction, build: impl FnOnce() -> Universe<T>) -> Arc<Universe<T>> {
+        if let Some(universe) = self.0.read().unwrap().get(&func.name) {
+            return Arc::clone(universe);
+        }
+
+        // Another thread may have built the universe in the meantime
+        let mut cache = self.0.write().unwrap();
+        Arc::clone(
+            cache
+                .entry(func.name.clone())
+                .or_insert_with(|| Arc::new(build())),
+        )
+    }
+}
+
+#[derive(Clone)]
+/// A set stored as a bit vector over the items of a universe
+pub enum BitSet<T> {
+    /// A set that is not tied to a universe yet, such as the meet of no values
+    Loose(Vec<T>),
+    /// A bit vector over a universe
+    Dense(Arc<Universe<T>>, Vec<u64>),
+}
+
+impl<T: Clone + Eq + Hash> BitSet<T> {
+    /// The empty set over a universe
+    pub fn empty(universe: Arc<Universe<T>>) -> Self {
+        let words = vec![0; universe.words()];
+        BitSet::Dense(universe, words)
+    }
+
+    /// The empty set, not tied to any universe
+    pub fn loose() -> Self {
+        BitSet::Loose(Vec::new())
+    }
+
+    fn universe(&self) -> Option<&Arc<Universe<T>>> {
+        match self {
+            BitSet::Loose(_) => None,
+            BitSet::Dense(universe, _) => Some(universe),
+        }
+    }
+
+    pub fn contains(&self, item: &T) -> bool {
+        match self {
+            BitSet::Loose(items) => items.contains(item),
+            BitSet::Dense(universe, words) => {
+                universe.index.get(item).is_some_and(|&i| bit(words, i))
+            }
+        }
+    }
+
+    /// Insert an item. Items of a dense set must belong to its universe.
+    pub fn insert(&mut self, item: T) {
+        match self {
+            BitSet::Loose(items) => {
+                if !items.contains(&item) {
+                    items.push(item);
+                }
+            }
+            BitSet::Dense(universe, words) => {
+                let i = universe.index[&item];
+                words[i / BITS] |= 1 << (i % BITS);
+            }
+        }
+    }
+
+    pub fn remove(&mut self, item: &T) {
+        match self {
+            BitSet::Loose(items) => items.retain(|x| x != item),
+            BitSet::Dense(universe, words) => {
+                if let Some(&i) = universe.index.get(item) {
+                    words[i / BITS] &= !(1 << (i % BITS));
+                }
+            }
+        }
+    }
+
+    /// Keep only the items for which `f` returns true
+    pub fn retain(&mut self, mut f: impl FnMut(&T) -> bool) {
+        match self {
+            BitSet::Loose(items) => items.retain(|x| f(x)),
+            BitSet::Dense(universe, words) => {
+                for (i, item) in universe.items.iter().enumerate() {
+                    if bit(words, i) && !f(item) {
+                        words[i / BITS] &= !(1 << (i % BITS));
+                    }
+                }
+            }
+        }
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &T> {
+        match self {
+            BitSet::Loose(items) => Either::Left(items.iter()),
+            BitSet::Dense(universe, words) => Either::Right(
+                universe
+                    .items
+                    .iter()
+                    .enumerate()
+                    .filter(|&(i, _)| bit(words, i))
+                    .map(|(_, item)| item),
+            ),
+        }
+    }
+
+    /// The bits of this set over `universe`
+    fn bits_in(&self, universe: &Arc<Universe<T>>) -> Cow<'_, [u64]> {
+        match self {
+            BitSet::Dense(u, words) if Arc::ptr_eq(u, universe) => Cow::Borrowed(words),
+            _ => {
+                let mut words = vec![0; universe.words()];
+                for item in self.iter() {
+                    let i = universe.index[item];
+                    words[i / BITS] |= 1 << (i % BITS);
+                }
+                Cow::Owned(words)
+            }
+        }
+    }
+
+    /// Union of the given sets
+    pub fn union(sets: &[Self]) -> Self {
+        match sets.iter().find_map(|s| s.universe()) {
+            Some(universe) => {
+                let mut words = vec![0; universe.words()];
+                for set in sets {
+                    for (w, b) in words.iter_mut().zip(set.bits_in(universe).iter()) {
+                        *w |= b;
+                    }
+                }
+                BitSet::Dense(Arc::clone(universe), words)
+            }
+            None => {
+                let mut union = BitSet::loose();
+                for item in sets.iter().flat_map(|s| s.iter()) {
+                    union.insert(item.clone());
+                }
+                union
+            }
+        }
+    }
+
+    /// Intersection of the given sets. There must be at least one set.
+    pub fn intersection(sets: &[Self]) -> Self {
+        match sets.iter().find_map(|s| s.universe()) {
+            Some(universe) => {
+                let mut words = vec![u64::MAX; universe.words()];
+                for set in sets {
+                    for (w, b) in words.iter_mut().zip(set.bits_in(universe).iter()) {
+                        *w &= b;
+                    }
+                }
+                BitSet::Dense(Arc::clone(universe), words)
+            }
+            None => {
+                let mut intersection = sets[0].clone();
+                intersection.retain(|item| sets.iter().all(|s| s.contains(item)));
+                intersection
+            }
+        }
+    }
+}
+
+impl<T: Clone + Eq + Hash> PartialEq for BitSet<T> {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (BitSet::Dense(a, x), BitSet::Dense(b, y)) if Arc::ptr_eq(a, b) => x == y,
+            _ => {
+                self.iter().count() == other.iter().count()
+                    && self.iter().all(|item| other.contains(item))
+            }
+        }
+    }
+}
+
+impl<T: Clone + Eq + Hash> Eq for BitSet<T> {}
+
+impl<T: Clone + Eq + Hash> PartialEq<HashSet<T>> for BitSet<T> {
+    fn eq(&self, other: &HashSet<T>) -> bool {
+        self.iter().count() == other.len() && self.iter().all(|item| other.contains(item))
+    }
+}
+
+impl<T: Clone + Eq + Hash + Debug> Debug for BitSet<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_set().entries(self.iter()).finish()
+    }
+}
+
+impl<T: Clone + Eq + Hash + Serialize> Serialize for BitSet<T> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.collect_seq(self.iter())
+    }
+}
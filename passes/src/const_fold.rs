@@ -0,0 +1,82 @@
+use crate::const_prop::{Value, step};
+use bril_utils::{
+    Dataflow, HashableLiteral, InstrExt,
+    bril_rs::{ConstOps, EffectOps, Instruction, Literal},
+};
+use std::collections::HashMap;
+
+/// The rewritten form of `insn`, given the values of the variables after it, or
+/// None if it stays as is. Branches write no variable, so their condition has
+/// the same value before and after them.
+fn fold(insn: &Instruction, vals: &HashMap<String, Value>) -> Option<Instruction> {
+    match insn {
+        // Calls and other effectful instructions have to run even if their
+        // result is known
+        Instruction::Value {
+            dest, op_type, pos, ..
+        } if insn.is_pure() => match vals.get(dest)? {
+            Value::Const(c) => Some(Instruction::Constant {
+                dest: dest.clone(),
+                op: ConstOps::Const,
+                pos: pos.clone(),
+                const_type: op_type.clone(),
+                value: c.clone().into(),
+            }),
+            Value::Any => None,
+        },
+        Instruction::Effect {
+            op: EffectOps::Branch,
+            args,
+            labels,
+            pos,
+            ..
+        } => {
+            let taken = match vals.get(&args[0])? {
+                Value::Const(c) if *c == HashableLiteral::from(Literal::Bool(true)) => &labels[0],
+                Value::Const(_) => &labels[1],
+                Value::Any => return None,
+            };
+            Some(Instruction::Effect {
+                op: EffectOps::Jump,
+                args: Vec::new(),
+                funcs: Vec::new(),
+                labels: vec![taken.clone()],
+                pos: pos.clone(),
+            })
+        }
+        _ => None,
+    }
+}
+
+/// Replace instructions whose result is a known constant by `const`, and
+/// branches on a known condition by jumps, given the results of `ConstProp`.
+/// Returns the new instructions of every block, or None if nothing changed.
+pub fn fold_constants(
+    dataflow: &Dataflow<HashMap<String, Value>>,
+) -> Option<Vec<Vec<Instruction>>> {
+    let func = dataflow.cfg.func();
+    let mut changed = false;
+
+    let instrs: Vec<Vec<_>> = (0..dataflow.cfg.len())
+        .map(|i| {
+            let mut vals = dataflow.in_vals[i].clone();
+
+            func.get(i)
+                .iter()
+                .map(|insn| {
+                    step(&mut vals, insn);
+                    match fold(insn, &vals) {
+                        Some(new) => {
+                            log::debug!("Replacing {} by {}", insn, new);
+                            changed = true;
+                            new
+                        }
+                        None => insn.clone(),
+                    }
+                })
+                .collect()
+        })
+        .collect();
+
+    changed.then_some(instrs)
+}
@@ -1,6 +1,13 @@
-use bril_utils::{BBFunction, BasicBlock, Foldable, HashableLiteral};
-use std::{collections::HashMap, fmt::Display};
-use utils::DataflowSpec;
+use bril_utils::{
+    BBFunction, BasicBlock, Foldable, HashableLiteral, InstrExt,
+    bril_rs::{EffectOps, Instruction, Literal, ValueOps},
+};
+use serde::Serialize;
+use std::{
+    collections::{HashMap, HashSet},
+    fmt::Display,
+};
+use utils::{DataflowSpec, block_label};
 
 #[derive(Clone, Debug, Eq, Hash, PartialEq)]
 pub enum Value {
@@ -16,12 +23,115 @@ impl Display for Value {
         }
     }
 }
+impl Serialize for Value {
+    /// Constants are serialized as Bril literals, and non-constant values as null
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        match self {
+            Value::Any => serializer.serialize_none(),
+            Value::Const(l) => Literal::from(l.clone()).serialize(serializer),
+        }
+    }
+}
+
+/// If block `from` ends in a branch with distinct targets and `to` is one of
+/// them, the condition variable and the value it has along that edge
+pub(crate) fn branch_condition(func: &BBFunction, from: usize, to: usize) -> Option<(&str, bool)> {
+    let Some(Instruction::Effect {
+        op: EffectOps::Branch,
+        args,
+        labels,
+        ..
+    }) = func.get(from).iter().last()
+    else {
+        return None;
+    };
+
+    if labels[0] == labels[1] {
+        return None;
+    }
+
+    match block_label(func.get(to)) {
+        Some(label) if label == labels[0] => Some((&args[0], true)),
+        Some(label) if label == labels[1] => Some((&args[0], false)),
+        _ => None,
+    }
+}
+
+/// If `cond` is last written in block `from` by an equality test of two
+/// variables that are not written again afterwards, the two variables
+fn equality<'a>(func: &'a BBFunction, from: usize, cond: &str) -> Option<(&'a str, &'a str)> {
+    let insns = func.get(from).iter().collect::<Vec<_>>();
+    let mut written = HashSet::new();
+
+    for insn in insns.into_iter().rev() {
+        let Some(dest) = insn.dest() else {
+            continue;
+        };
+        if dest == cond {
+            return match insn {
+                Instruction::Value {
+                    op: ValueOps::Eq,
+                    args,
+                    ..
+                } if !written.contains(&args[0]) && !written.contains(&args[1]) => {
+                    Some((&args[0], &args[1]))
+                }
+                _ => None,
+            };
+        }
+        written.insert(dest);
+    }
+    None
+}
+
+/// Update the values of the variables at a point after `insn`
+pub(crate) fn step(vals: &mut HashMap<String, Value>, insn: &Instruction) {
+    // An argument that is not bound yet leaves the destination unbound as
+    // well, which keeps the transfer function monotone
+    if insn
+        .args()
+        .into_iter()
+        .flatten()
+        .any(|arg| !vals.contains_key(&arg))
+    {
+        if let Some(dest) = insn.dest() {
+            vals.remove(&dest);
+        }
+        return;
+    }
+
+    let folded = insn.fold(|arg| {
+        vals.get(arg).and_then(|v| match v {
+            Value::Const(c) => Some(c.clone().into()),
+            Value::Any => None,
+        })
+    });
+
+    match folded {
+        Some((dest, Some(v))) => vals.insert(dest, Value::Const(v.into())),
+        Some((dest, None)) => vals.insert(dest, Value::Any),
+        // Anything else that writes a variable leaves it unknown
+        None => insn.dest().and_then(|dest| vals.insert(dest, Value::Any)),
+    };
+}
+
 #[derive(Default)]
 pub struct ConstProp;
 
 impl DataflowSpec for ConstProp {
     type Val = HashMap<String, Value>;
 
+    fn entry(&self, func: &BBFunction) -> Self::Val {
+        // Arguments can be anything
+        func.args
+            .iter()
+            .map(|arg| (arg.name.clone(), Value::Any))
+            .collect()
+    }
+
     fn init(&self, _: &BBFunction) -> Self::Val {
         HashMap::default()
     }
@@ -30,7 +140,9 @@ impl DataflowSpec for ConstProp {
         let mut out_vals = HashMap::new();
 
         // For every key, if it has multiple different bindings, set it to Any
-        // Otherwise, set it to the value
+        // Otherwise, set it to the value. Arguments are bound on entry, so a
+        // key is only missing from a predecessor that is not reached yet or on
+        // which the variable is undefined, and reading it there is an error.
         for (name, bind) in in_vals.iter().flat_map(|v| v.iter()) {
             if let Some(v) = out_vals.get(name) {
                 if v != bind {
@@ -45,25 +157,42 @@ impl DataflowSpec for ConstProp {
     }
 
     fn transfer(&self, block: &BasicBlock, in_val: &Self::Val) -> Self::Val {
+        // Fold each instruction with the values left by the ones before it
         let mut out_vals = in_val.clone();
-
         for insn in block.iter() {
-            if let Some((dest, val)) = insn.fold(|arg| {
-                in_val.get(arg).and_then(|v| match v {
-                    Value::Const(c) => Some(c.clone().into()),
-                    Value::Any => None,
-                })
-            }) {
-                out_vals.insert(
-                    dest,
-                    match val {
-                        Some(v) => Value::Const(v.into()),
-                        None => Value::Any,
-                    },
-                );
+            step(&mut out_vals, insn);
+        }
+        out_vals
+    }
+
+    fn edge_transfer(
+        &self,
+        func: &BBFunction,
+        from: usize,
+        to: usize,
+        mut val: Self::Val,
+    ) -> Self::Val {
+        // The condition of a branch is known along each of its edges
+        let Some((cond, taken)) = branch_condition(func, from, to) else {
+            return val;
+        };
+
+        // Where an equality test holds, a variable equal to a constant is
+        // that constant as well
+        if taken {
+            if let Some((a, b)) = equality(func, from, cond) {
+                let known = match (val.get(a), val.get(b)) {
+                    (Some(c @ Value::Const(_)), _) => Some((b, c.clone())),
+                    (_, Some(c @ Value::Const(_))) => Some((a, c.clone())),
+                    _ => None,
+                };
+                if let Some((var, c)) = known {
+                    val.insert(var.to_string(), c);
+                }
             }
         }
 
-        out_vals
+        val.insert(cond.to_string(), Value::Const(Literal::Bool(taken).into()));
+        val
     }
 }
@@ -0,0 +1,111 @@
+use crate::available_expr::{Expr, Set, make_available};
+use bril_utils::{
+    BBFunction, Dataflow, InstrExt,
+    bril_rs::{Instruction, ValueOps},
+};
+use std::collections::HashMap;
+
+/// The variable that holds the value of each expression wherever it is
+/// available. A variable only ever assigned one expression holds it: every
+/// path to a point where the expression is available computes it after its
+/// arguments last changed, and computing it again gives the same value.
+fn holders(func: &BBFunction) -> HashMap<Expr, String> {
+    // The single expression assigned to each variable, or None if there are
+    // several or any other definition
+    let mut defs: HashMap<String, Option<Expr>> = HashMap::new();
+    for instr in func.blocks.iter().flat_map(|block| block.iter()) {
+        let Some(dest) = instr.dest() else {
+            continue;
+        };
+
+        // An expression that uses its own destination is not held by it
+        let expr = Expr::new(instr).filter(|expr| !expr.contains(&dest));
+        defs.entry(dest)
+            .and_modify(|def| {
+                if *def != expr {
+                    *def = None;
+                }
+            })
+            .or_insert(expr);
+    }
+
+    let mut holders = HashMap::new();
+    for (var, expr) in defs {
+        if let Some(expr) = expr {
+            let holder = holders.entry(expr).or_insert_with(|| var.clone());
+            // Pick the same holder no matter the order of the map
+            if var < *holder {
+                *holder = var;
+            }
+        }
+    }
+    holders
+}
+
+/// The variable that already holds the value computed by `instr`, if the
+/// instruction is worth replacing by a copy of it
+fn holder<'a>(
+    holders: &'a HashMap<Expr, String>,
+    available: &Set,
+    instr: &Instruction,
+) -> Option<&'a String> {
+    match Expr::new(instr)? {
+        // Constants and copies are as cheap as the copy itself
+        Expr::Const(_) | Expr::Op(ValueOps::Id, _) => None,
+        expr if available.contains(&expr) => holders
+            .get(&expr)
+            // The instruction is one of the holder's own definitions
+            .filter(|var| Some(*var) != instr.dest().as_ref()),
+        _ => None,
+    }
+}
+
+/// Replace recomputed expressions by copies of a variable that already holds
+/// their value, given the results of `AvailableExpr`. Returns the new
+/// instructions of every block, or None if nothing was replaced.
+pub fn common_subexprs(dataflow: &Dataflow<Set>) -> Option<Vec<Vec<Instruction>>> {
+    let func = dataflow.cfg.func();
+    let holders = holders(func);
+    let mut changed = false;
+
+    let instrs: Vec<Vec<_>> = (0..dataflow.cfg.len())
+        .map(|i| {
+            // Blocks that are never reached have everything available, so
+            // leave them be
+            let mut available = match &dataflow.in_vals[i] {
+                Set::Full => Set::empty(),
+                finite => finite.clone(),
+            };
+
+            func.get(i)
+                .iter()
+                .map(|instr| {
+                    let mut new = instr.clone();
+                    if let (
+                        Some(var),
+                        Instruction::Value {
+                            op,
+                            args,
+                            funcs,
+                            labels,
+                            ..
+                        },
+                    ) = (holder(&holders, &available, instr), &mut new)
+                    {
+                        log::debug!("Replacing {} by a copy of {}", instr, var);
+                        *op = ValueOps::Id;
+                        *args = vec![var.clone()];
+                        funcs.clear();
+                        labels.clear();
+                        changed = true;
+                    }
+
+                    make_available(&mut available, instr);
+                    new
+                })
+                .collect()
+        })
+        .collect();
+
+    changed.then_some(instrs)
+}
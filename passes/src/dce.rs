@@ -0,0 +1,51 @@
+use bril_utils::{
+    Dataflow, InstrExt,
+    bril_rs::{Instruction, ValueOps},
+};
+use std::collections::HashSet;
+
+/// Whether an instruction can be removed when its destination is dead. Besides
+/// being pure, it must not trap, which division does on a zero divisor.
+fn is_removable(insn: &Instruction) -> bool {
+    insn.is_pure()
+        && !matches!(
+            insn,
+            Instruction::Value {
+                op: ValueOps::Div,
+                ..
+            }
+        )
+}
+
+/// Remove the pure instructions whose destination is dead, given the results
+/// of `LiveVars`. Returns the remaining instructions of every block, or None if
+/// nothing was removed.
+pub fn dead_code(dataflow: &Dataflow<HashSet<String>>) -> Option<Vec<Vec<Instruction>>> {
+    let func = dataflow.cfg.func();
+    let mut changed = false;
+
+    let instrs = (0..dataflow.cfg.len())
+        .map(|i| {
+            // Live variables at the end of the block
+            let mut live = dataflow.in_vals[i].clone();
+            let mut kept = Vec::new();
+
+            for insn in func.get(i).iter().rev() {
+                if let Some(dest) = insn.dest() {
+                    if is_removable(insn) && !live.contains(&dest) {
+                        changed = true;
+                        continue;
+                    }
+                    live.remove(&dest);
+                }
+                live.extend(insn.args().into_iter().flatten());
+                kept.push(insn.clone());
+            }
+
+            kept.reverse();
+            kept
+        })
+        .collect();
+
+    changed.then_some(instrs)
+}
@@ -0,0 +1,172 @@
+use bril_utils::{BBFunction, BasicBlock, Dataflow};
+use itertools::Itertools;
+use serde::Serialize;
+use std::{collections::BTreeSet, fmt::Debug};
+use utils::DataflowSpec;
+
+#[derive(Clone, PartialEq, Eq)]
+/// Set of block indices
+pub enum BlockSet {
+    /// Set of all blocks
+    Full,
+    /// Set of finite blocks
+    Finite(BTreeSet<usize>),
+}
+
+impl BlockSet {
+    fn intersect(&self, other: &Self) -> Self {
+        match (self, other) {
+            (BlockSet::Full, _) => other.clone(),
+            (_, BlockSet::Full) => self.clone(),
+            (BlockSet::Finite(a), BlockSet::Finite(b)) => {
+                BlockSet::Finite(a.intersection(b).copied().collect())
+            }
+        }
+    }
+
+    pub fn contains(&self, i: usize) -> bool {
+        match self {
+            BlockSet::Full => true,
+            BlockSet::Finite(set) => set.contains(&i),
+        }
+    }
+}
+
+impl Debug for BlockSet {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            BlockSet::Full => write!(f, "T"),
+            BlockSet::Finite(set) => write!(f, "{{{}}}", set.iter().join(", ")),
+        }
+    }
+}
+
+impl Serialize for BlockSet {
+    /// The full set is serialized as "T", like its debug representation
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        match self {
+            BlockSet::Full => serializer.serialize_str("T"),
+            BlockSet::Finite(set) => serializer.collect_seq(set),
+        }
+    }
+}
+
+#[derive(Default)]
+/// The blocks dominating each block. Blocks that are unreachable from the
+/// entry are dominated by every block.
+pub struct Dominators;
+
+impl DataflowSpec for Dominators {
+    type Val = BlockSet;
+
+    fn entry(&self, _: &BBFunction) -> BlockSet {
+        // Nothing dominates the entry block but itself
+        BlockSet::Finite(BTreeSet::new())
+    }
+
+    fn init(&self, _: &BBFunction) -> BlockSet {
+        BlockSet::Full
+    }
+
+    fn meet(&self, in_vals: &[BlockSet]) -> BlockSet {
+        // Set intersection
+        in_vals
+            .iter()
+            .fold(BlockSet::Full, |acc, val| acc.intersect(val))
+    }
+
+    fn transfer(&self, block: &BasicBlock, in_val: &BlockSet) -> BlockSet {
+        let mut out_val = in_val.clone();
+        if let BlockSet::Finite(set) = &mut out_val {
+            set.insert(block.idx);
+        }
+        out_val
+    }
+}
+
+/// Dominator tree and dominance frontiers of a function, derived from the
+/// results of the Dominators pass
+pub struct DominatorTree {
+    idoms: Vec<Option<usize>>,
+    children: Vec<Vec<usize>>,
+    frontiers: Vec<BTreeSet<usize>>,
+}
+
+impl DominatorTree {
+    pub fn new(dataflow: &Dataflow<BlockSet>) -> Self {
+        let n = dataflow.cfg.len();
+        let doms = &dataflow.out_vals;
+        let size = |i: usize| match &doms[i] {
+            BlockSet::Full => usize::MAX,
+            BlockSet::Finite(set) => set.len(),
+        };
+
+        // The immediate dominator is the strict dominator that is dominated by
+        // all others, which is the one with the most dominators
+        let idoms = (0..n)
+            .map(|i| match &doms[i] {
+                BlockSet::Full => None,
+                BlockSet::Finite(set) => set
+                    .iter()
+                    .copied()
+                    .filter(|&d| d != i)
+                    .max_by_key(|&d| size(d)),
+            })
+            .collect_vec();
+
+        let mut children = vec![Vec::new(); n];
+        for (i, idom) in idoms.iter().enumerate() {
+            if let Some(d) = idom {
+                children[*d].push(i);
+            }
+        }
+
+        // A block is in the frontier of every block on the dominator tree path
+        // from each of its predecessors up to its immediate dominator. This
+        // also covers an entry block with a single back edge, which has no
+        // immediate dominator.
+        let mut frontiers = vec![BTreeSet::new(); n];
+        for i in 0..n {
+            let preds = dataflow.cfg.preds(i);
+            if doms[i] == BlockSet::Full {
+                continue;
+            }
+
+            for &p in preds.iter() {
+                let mut runner = Some(p);
+                while let Some(r) = runner {
+                    if Some(r) == idoms[i] || doms[r] == BlockSet::Full {
+                        break;
+                    }
+                    frontiers[r].insert(i);
+                    runner = idoms[r];
+                }
+            }
+        }
+
+        Self {
+            idoms,
+            children,
+            frontiers,
+        }
+    }
+
+    /// The immediate dominator of a block, or None for entry and unreachable
+    /// blocks
+    pub fn idom(&self, i: usize) -> Option<usize> {
+        self.idoms[i]
+    }
+
+    /// The blocks immediately dominated by a block
+    pub fn children(&self, i: usize) -> &[usize] {
+        &self.children[i]
+    }
+
+    /// The dominance frontier of a block
+    pub fn frontier(&self, i: usize) -> &BTreeSet<usize> {
+        &self.frontiers[i]
+    }
+}
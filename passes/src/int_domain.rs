@@ -0,0 +1,126 @@
+use bril_utils::{
+    BBFunction, BasicBlock, InstrExt,
+    bril_rs::{Instruction, Literal, Type, ValueOps},
+};
+use itertools::Itertools;
+use serde::Serialize;
+use std::{collections::BTreeMap, fmt::Debug};
+
+/// A non-relational abstraction of a single integer
+pub trait IntDomain: Copy + Eq + Debug + Serialize + Send + Sync {
+    /// Any integer
+    fn top() -> Self;
+
+    /// Least upper bound of two abstract values
+    fn join(self, other: Self) -> Self;
+
+    /// Abstraction of a constant
+    fn constant(a: i64) -> Self;
+
+    /// Abstraction of `add`, `sub`, `mul` and `div`
+    fn arith(op: ValueOps, a: Self, b: Self) -> Self;
+
+    /// Whether a concrete integer is one of the values this abstracts
+    fn contains(self, a: i64) -> bool;
+}
+
+#[derive(Clone, PartialEq, Eq, Serialize)]
+/// Abstract values of the integer variables defined so far
+pub struct IntState<D>(BTreeMap<String, D>);
+
+impl<D: IntDomain> IntState<D> {
+    /// Abstract value of a variable, if it is defined
+    pub fn get(&self, name: &str) -> Option<D> {
+        self.0.get(name).copied()
+    }
+
+    /// Every defined variable with its abstract value
+    pub fn iter(&self) -> impl Iterator<Item = (&String, D)> {
+        self.0.iter().map(|(name, val)| (name, *val))
+    }
+}
+
+impl<D: Debug> Debug for IntState<D> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{{{}}}",
+            self.0
+                .iter()
+                .map(|(name, val)| format!("{}: {:?}", name, val))
+                .join(", ")
+        )
+    }
+}
+
+/// Integer arguments can be anything
+pub(crate) fn entry<D: IntDomain>(func: &BBFunction) -> IntState<D> {
+    IntState(
+        func.args
+            .iter()
+            .filter(|arg| arg.arg_type == Type::Int)
+            .map(|arg| (arg.name.clone(), D::top()))
+            .collect(),
+    )
+}
+
+pub(crate) fn init<D: IntDomain>() -> IntState<D> {
+    IntState(BTreeMap::new())
+}
+
+/// Join the values of every variable. A variable missing from an input is not
+/// defined on that path, so it does not contribute.
+pub(crate) fn meet<D: IntDomain>(in_vals: &[IntState<D>]) -> IntState<D> {
+    let mut out_vals = BTreeMap::new();
+    for (name, val) in in_vals.iter().flat_map(|v| v.0.iter()) {
+        out_vals
+            .entry(name.clone())
+            .and_modify(|v: &mut D| *v = v.join(*val))
+            .or_insert(*val);
+    }
+    IntState(out_vals)
+}
+
+pub(crate) fn transfer<D: IntDomain>(block: &BasicBlock, in_val: &IntState<D>) -> IntState<D> {
+    let mut vals = in_val.0.clone();
+
+    for instr in block.iter() {
+        let Some(dest) = instr.dest() else {
+            continue;
+        };
+
+        let val = match instr {
+            Instruction::Constant {
+                value: Literal::Int(a),
+                ..
+            } => Some(D::constant(*a)),
+            Instruction::Value {
+                op,
+                args,
+                op_type: Type::Int,
+                ..
+            } => {
+                // An argument that is not bound yet leaves the destination
+                // unbound as well, which keeps the transfer function monotone
+                let arg = |k: usize| vals.get(&args[k]).copied();
+                match op {
+                    ValueOps::Id => arg(0),
+                    ValueOps::Add | ValueOps::Sub | ValueOps::Mul | ValueOps::Div => {
+                        arg(0).zip(arg(1)).map(|(a, b)| D::arith(*op, a, b))
+                    }
+                    // Calls, loads and any other operations
+                    _ => Some(D::top()),
+                }
+            }
+            // Not an integer
+            _ => None,
+        };
+
+        match val {
+            Some(val) => vals.insert(dest, val),
+            None => vals.remove(&dest),
+        };
+    }
+
+    IntState(vals)
+}
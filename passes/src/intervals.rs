@@ -0,0 +1,310 @@
+use bril_utils::{
+    BBFunction, BasicBlock, InstrExt,
+    bril_rs::{Instruction, Literal, Type, ValueOps},
+};
+use itertools::Itertools;
+use serde::Serialize;
+use std::{
+    collections::BTreeMap,
+    fmt::{Debug, Display},
+};
+use utils::DataflowSpec;
+
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+/// A bound of an interval
+pub enum Bound {
+    NegInf,
+    Int(i64),
+    PosInf,
+}
+
+impl Bound {
+    /// The integer a bound stands for. Bril integers wrap at 64 bits, so the
+    /// infinite bounds are the smallest and largest integers.
+    fn value(self) -> i128 {
+        match self {
+            Bound::NegInf => i64::MIN.into(),
+            Bound::PosInf => i64::MAX.into(),
+            Bound::Int(a) => a.into(),
+        }
+    }
+
+    /// The bound of an integer, or None if it is outside the 64-bit range
+    fn from_value(a: i128) -> Option<Self> {
+        match i64::try_from(a).ok()? {
+            i64::MIN => Some(Bound::NegInf),
+            i64::MAX => Some(Bound::PosInf),
+            a => Some(Bound::Int(a)),
+        }
+    }
+}
+
+impl Display for Bound {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Bound::NegInf => "-inf".fmt(f),
+            Bound::PosInf => "inf".fmt(f),
+            Bound::Int(a) => a.fmt(f),
+        }
+    }
+}
+
+impl Serialize for Bound {
+    /// Infinite bounds are serialized as "-inf" and "inf"
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        match self {
+            Bound::Int(a) => serializer.serialize_i64(*a),
+            _ => serializer.collect_str(self),
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Serialize)]
+/// A nonempty range of integers `[lo, hi]`
+pub struct Interval {
+    pub lo: Bound,
+    pub hi: Bound,
+}
+
+impl Interval {
+    fn top() -> Self {
+        Interval {
+            lo: Bound::NegInf,
+            hi: Bound::PosInf,
+        }
+    }
+
+    /// The range holding only `a`
+    pub fn constant(a: i64) -> Self {
+        let bound = Bound::from_value(a.into()).unwrap();
+        Interval {
+            lo: bound,
+            hi: bound,
+        }
+    }
+
+    /// Whether a concrete integer is in the range. The ends are compared as
+    /// integers, since the infinite bounds stand for the smallest and largest
+    /// integers.
+    pub fn contains(&self, a: i64) -> bool {
+        let a = i128::from(a);
+        self.lo.value() <= a && a <= self.hi.value()
+    }
+
+    /// Smallest interval containing both intervals
+    fn join(&self, other: &Self) -> Self {
+        Interval {
+            lo: self.lo.min(other.lo),
+            hi: self.hi.max(other.hi),
+        }
+    }
+
+    /// Standard interval widening: bounds that grew are pushed to infinity
+    fn widen(&self, new: &Self) -> Self {
+        Interval {
+            lo: if new.lo < self.lo {
+                Bound::NegInf
+            } else {
+                self.lo
+            },
+            hi: if new.hi > self.hi {
+                Bound::PosInf
+            } else {
+                self.hi
+            },
+        }
+    }
+
+    /// Smallest interval containing all the given values. Bril arithmetic
+    /// wraps around, so if any value is outside the 64-bit range the result
+    /// could be anything.
+    fn hull(values: impl IntoIterator<Item = i128>) -> Self {
+        let values = values.into_iter().collect_vec();
+        let lo = Bound::from_value(*values.iter().min().unwrap());
+        let hi = Bound::from_value(*values.iter().max().unwrap());
+        match (lo, hi) {
+            (Some(lo), Some(hi)) => Interval { lo, hi },
+            _ => Interval::top(),
+        }
+    }
+
+    fn add(&self, other: &Self) -> Self {
+        Interval::hull([
+            self.lo.value() + other.lo.value(),
+            self.hi.value() + other.hi.value(),
+        ])
+    }
+
+    fn sub(&self, other: &Self) -> Self {
+        Interval::hull([
+            self.lo.value() - other.hi.value(),
+            self.hi.value() - other.lo.value(),
+        ])
+    }
+
+    /// The results of an operation on every pair of ends of the intervals
+    fn corners(&self, other: &Self, op: impl Fn(i128, i128) -> i128) -> [i128; 4] {
+        let (a, b) = (self.lo.value(), self.hi.value());
+        let (c, d) = (other.lo.value(), other.hi.value());
+        [op(a, c), op(a, d), op(b, c), op(b, d)]
+    }
+
+    fn mul(&self, other: &Self) -> Self {
+        Interval::hull(self.corners(other, |a, b| a * b))
+    }
+
+    fn div(&self, other: &Self) -> Self {
+        if other.contains(0) {
+            // Division by an interval containing zero
+            Interval::top()
+        } else {
+            // Truncating division is monotone in each argument when the
+            // divisor has a single sign, so the ends are reached at corners
+            Interval::hull(self.corners(other, |a, b| a / b))
+        }
+    }
+}
+
+impl Debug for Interval {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "[{}, {}]", self.lo, self.hi)
+    }
+}
+
+#[derive(Clone, PartialEq, Eq, Serialize)]
+/// Ranges of the integer variables defined so far
+pub struct Intervals(BTreeMap<String, Interval>);
+
+impl Intervals {
+    /// Every defined variable with its range
+    pub fn iter(&self) -> impl Iterator<Item = (&String, &Interval)> {
+        self.0.iter()
+    }
+}
+
+impl Debug for Intervals {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{{{}}}",
+            self.0
+                .iter()
+                .map(|(name, range)| format!("{}: {:?}", name, range))
+                .join(", ")
+        )
+    }
+}
+
+/// Integer range analysis. Loop heads are widened after being visited
+/// `widen_after` times, so loops terminate.
+pub struct IntervalAnalysis {
+    widen_after: usize,
+}
+
+impl IntervalAnalysis {
+    /// Create a new IntervalAnalysis that widens after `widen_after` visits
+    pub fn new(widen_after: usize) -> Self {
+        Self { widen_after }
+    }
+}
+
+impl Default for IntervalAnalysis {
+    fn default() -> Self {
+        Self::new(3)
+    }
+}
+
+impl DataflowSpec for IntervalAnalysis {
+    type Val = Intervals;
+
+    fn entry(&self, func: &BBFunction) -> Intervals {
+        // Integer arguments can be anything
+        Intervals(
+            func.args
+                .iter()
+                .filter(|arg| arg.arg_type == Type::Int)
+                .map(|arg| (arg.name.clone(), Interval::top()))
+                .collect(),
+        )
+    }
+
+    fn init(&self, _: &BBFunction) -> Intervals {
+        Intervals(BTreeMap::new())
+    }
+
+    fn meet(&self, in_vals: &[Intervals]) -> Intervals {
+        // Join the ranges of every variable. A variable missing from an input
+        // is not defined on that path, so it does not widen the range.
+        let mut out_vals = BTreeMap::new();
+        for (name, range) in in_vals.iter().flat_map(|v| v.0.iter()) {
+            out_vals
+                .entry(name.clone())
+                .and_modify(|r: &mut Interval| *r = r.join(range))
+                .or_insert(*range);
+        }
+        Intervals(out_vals)
+    }
+
+    fn transfer(&self, block: &BasicBlock, in_val: &Intervals) -> Intervals {
+        let mut vals = in_val.0.clone();
+
+        for instr in block.iter() {
+            let Some(dest) = instr.dest() else {
+                continue;
+            };
+
+            let range = match instr {
+                Instruction::Constant {
+                    value: Literal::Int(a),
+                    ..
+                } => Some(Interval::constant(*a)),
+                Instruction::Value {
+                    op,
+                    args,
+                    op_type: Type::Int,
+                    ..
+                } => {
+                    // An argument that is not bound yet leaves the destination
+                    // unbound as well, which keeps the transfer function monotone
+                    let arg = |k: usize| vals.get(&args[k]).copied();
+                    let both = || arg(0).zip(arg(1));
+                    match op {
+                        ValueOps::Id => arg(0),
+                        ValueOps::Add => both().map(|(a, b)| a.add(&b)),
+                        ValueOps::Sub => both().map(|(a, b)| a.sub(&b)),
+                        ValueOps::Mul => both().map(|(a, b)| a.mul(&b)),
+                        ValueOps::Div => both().map(|(a, b)| a.div(&b)),
+                        // Calls, loads and any other operations
+                        _ => Some(Interval::top()),
+                    }
+                }
+                // Not an integer
+                _ => None,
+            };
+
+            match range {
+                Some(range) => vals.insert(dest, range),
+                None => vals.remove(&dest),
+            };
+        }
+
+        Intervals(vals)
+    }
+
+    fn widen(&self, old: &Intervals, new: Intervals) -> Intervals {
+        let mut vals = new.0;
+        for (name, old_range) in old.0.iter() {
+            vals.entry(name.clone())
+                .and_modify(|r| *r = old_range.widen(r))
+                .or_insert(*old_range);
+        }
+        Intervals(vals)
+    }
+
+    fn widen_delay(&self) -> usize {
+        self.widen_after
+    }
+}
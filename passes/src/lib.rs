@@ -1,9 +1,33 @@
 mod available_expr;
+mod bitset;
+mod const_fold;
 mod const_prop;
+mod cse;
+mod dce;
+mod dominators;
+mod int_domain;
+mod intervals;
 mod live_vars;
+mod parity;
 mod reaching_defs;
+mod sccp;
+mod sign;
+mod uninit;
+mod very_busy_exprs;
 
-pub use available_expr::AvailableExpr;
-pub use const_prop::ConstProp;
-pub use live_vars::LiveVars;
-pub use reaching_defs::{ReachingDefinition, ReachingDefs};
+pub use available_expr::{AvailableExpr, AvailableExprBv, Set};
+pub use bitset::{BitSet, Universe};
+pub use const_fold::fold_constants;
+pub use const_prop::{ConstProp, Value};
+pub use cse::common_subexprs;
+pub use dce::dead_code;
+pub use dominators::{BlockSet, DominatorTree, Dominators};
+pub use int_domain::{IntDomain, IntState};
+pub use intervals::{Bound, Interval, IntervalAnalysis, Intervals};
+pub use live_vars::{LiveVars, LiveVarsBv};
+pub use parity::{Parity, ParityVal};
+pub use reaching_defs::{ReachingDefinition, ReachingDefs, ReachingDefsBv};
+pub use sccp::{Sccp, SccpVal};
+pub use sign::{Sign, SignVal};
+pub use uninit::{UninitDefs, UninitUse};
+pub use very_busy_exprs::VeryBusyExprs;
@@ -1,6 +1,7 @@
+use crate::bitset::{BitSet, Universe, UniverseCache};
 use bril_utils::{BBFunction, BasicBlock, InstrExt};
 use std::collections::HashSet;
-use utils::DataflowSpec;
+use utils::{DataflowSpec, GenKill, GenKillSpec};
 
 #[derive(Default)]
 pub struct LiveVars;
@@ -40,4 +41,93 @@ impl DataflowSpec for LiveVars {
 
         out_vals
     }
+
+    fn as_gen_kill(&self) -> Option<&dyn GenKillSpec<Val = Self::Val>> {
+        Some(self)
+    }
+}
+
+impl GenKillSpec for LiveVars {
+    fn gen_kill(&self, func: &BBFunction) -> Vec<GenKill<HashSet<String>>> {
+        // Variables used before being defined are generated, and all defined
+        // variables are killed
+        func.blocks
+            .iter()
+            .map(|block| {
+                let mut gen_set = HashSet::new();
+                let mut kill_set = HashSet::new();
+
+                for insn in block.iter().rev() {
+                    if let Some(dest) = insn.dest() {
+                        gen_set.remove(&dest);
+                        kill_set.insert(dest);
+                    }
+
+                    if let Some(args) = insn.args() {
+                        gen_set.extend(args);
+                    }
+                }
+
+                GenKill { gen_set, kill_set }
+            })
+            .collect()
+    }
+
+    fn apply(&self, gen_kill: &GenKill<HashSet<String>>, val: &mut HashSet<String>) {
+        val.retain(|name| !gen_kill.kill_set.contains(name));
+        val.extend(gen_kill.gen_set.iter().cloned());
+    }
+}
+
+#[derive(Default)]
+/// Live variables, with sets stored as bit vectors over the function's variables
+pub struct LiveVarsBv {
+    universes: UniverseCache<String>,
+}
+
+impl DataflowSpec for LiveVarsBv {
+    type Val = BitSet<String>;
+
+    fn reversed(&self) -> bool {
+        true
+    }
+
+    fn init(&self, func: &BBFunction) -> BitSet<String> {
+        BitSet::empty(self.universes.get(func, || {
+            Universe::new(
+                func.args.iter().map(|arg| arg.name.clone()).chain(
+                    func.blocks
+                        .iter()
+                        .flat_map(|block| block.iter())
+                        .flat_map(|insn| {
+                            insn.dest()
+                                .into_iter()
+                                .chain(insn.args().into_iter().flatten())
+                        }),
+                ),
+            )
+        }))
+    }
+
+    fn meet(&self, in_vals: &[BitSet<String>]) -> BitSet<String> {
+        BitSet::union(in_vals)
+    }
+
+    fn transfer(&self, block: &BasicBlock, in_val: &BitSet<String>) -> BitSet<String> {
+        let mut out_vals = in_val.clone();
+
+        for insn in block.iter().rev() {
+            if let Some(dest) = insn.dest() {
+                out_vals.remove(&dest);
+            }
+
+            if let Some(args) = insn.args() {
+                for arg in args {
+                    out_vals.insert(arg);
+                }
+            }
+        }
+
+        out_vals
+    }
 }
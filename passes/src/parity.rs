@@ -0,0 +1,92 @@
+use crate::int_domain::{self, IntDomain, IntState};
+use bril_utils::{BBFunction, BasicBlock, bril_rs::ValueOps};
+use serde::Serialize;
+use utils::DataflowSpec;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+/// Whether an integer is even or odd
+pub enum ParityVal {
+    /// No value, e.g. the result of a division by zero
+    Bottom,
+    Even,
+    Odd,
+    Top,
+}
+
+impl IntDomain for ParityVal {
+    fn top() -> Self {
+        ParityVal::Top
+    }
+
+    fn join(self, other: Self) -> Self {
+        match (self, other) {
+            (ParityVal::Bottom, x) | (x, ParityVal::Bottom) => x,
+            (x, y) if x == y => x,
+            _ => ParityVal::Top,
+        }
+    }
+
+    fn constant(a: i64) -> Self {
+        if a % 2 == 0 {
+            ParityVal::Even
+        } else {
+            ParityVal::Odd
+        }
+    }
+
+    /// Wrapping arithmetic preserves parity, so this is exact for `add`, `sub`
+    /// and `mul`
+    fn arith(op: ValueOps, a: Self, b: Self) -> Self {
+        use ParityVal::*;
+
+        match (op, a, b) {
+            (_, Bottom, _) | (_, _, Bottom) => Bottom,
+            (ValueOps::Add | ValueOps::Sub, Top, _) | (ValueOps::Add | ValueOps::Sub, _, Top) => {
+                Top
+            }
+            (ValueOps::Add | ValueOps::Sub, x, y) => {
+                if x == y {
+                    Even
+                } else {
+                    Odd
+                }
+            }
+            (ValueOps::Mul, Even, _) | (ValueOps::Mul, _, Even) => Even,
+            (ValueOps::Mul, Odd, Odd) => Odd,
+            _ => Top,
+        }
+    }
+
+    fn contains(self, a: i64) -> bool {
+        match self {
+            ParityVal::Bottom => false,
+            ParityVal::Top => true,
+            parity => parity == Self::constant(a),
+        }
+    }
+}
+
+#[derive(Default)]
+/// The parity of every integer variable
+pub struct Parity;
+
+impl DataflowSpec for Parity {
+    type Val = IntState<ParityVal>;
+
+    fn entry(&self, func: &BBFunction) -> Self::Val {
+        int_domain::entry(func)
+    }
+
+    fn init(&self, _: &BBFunction) -> Self::Val {
+        int_domain::init()
+    }
+
+    fn meet(&self, in_vals: &[Self::Val]) -> Self::Val {
+        int_domain::meet(in_vals)
+    }
+
+    fn transfer(&self, block: &BasicBlock, in_val: &Self::Val) -> Self::Val {
+        int_domain::transfer(block, in_val)
+    }
+}
@@ -1,19 +1,77 @@
+use crate::bitset::{BitSet, Universe, UniverseCache};
 use bril_utils::{BBFunction, BasicBlock, InstrExt};
-use std::{collections::HashSet, fmt::Debug, hash::Hash};
-use utils::DataflowSpec;
+use serde::Serialize;
+use std::{
+    collections::{HashMap, HashSet},
+    fmt::Debug,
+    hash::Hash,
+};
+use utils::{DataflowSpec, GenKill, GenKillSpec};
 
-#[derive(Clone, Eq, Hash, PartialEq, PartialOrd)]
+#[derive(Clone, Eq, Hash, PartialEq, PartialOrd, Serialize)]
 pub struct ReachingDefinition {
-    name: String,
-    block: usize,
+    pub name: String,
+    pub block: usize,
+    /// Index of the defining instruction in its block, or None if the name is
+    /// defined on entry to the function, like an argument
+    pub instr: Option<usize>,
+}
+
+impl ReachingDefinition {
+    /// A name defined on entry to the function
+    pub fn entry(name: String) -> Self {
+        Self {
+            name,
+            block: 0,
+            instr: None,
+        }
+    }
 }
 
 impl Debug for ReachingDefinition {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "{} @ {}", self.name, self.block)
+        match self.instr {
+            Some(instr) => write!(f, "{} @ {}.{}", self.name, self.block, instr),
+            None => write!(f, "{} @ entry", self.name),
+        }
     }
 }
 
+/// The definitions of a block that reach its end, which are the last
+/// definition of each name
+fn block_defs(block: &BasicBlock) -> impl Iterator<Item = ReachingDefinition> {
+    let mut last = HashMap::new();
+    for (k, insn) in block.iter().enumerate() {
+        if let Some(name) = insn.dest() {
+            last.insert(name, k);
+        }
+    }
+
+    last.into_iter().map(|(name, k)| ReachingDefinition {
+        name,
+        block: block.idx,
+        instr: Some(k),
+    })
+}
+
+/// Every definition of a block, including those overwritten later in it
+fn instr_defs(block: &BasicBlock) -> impl Iterator<Item = ReachingDefinition> {
+    block.iter().enumerate().filter_map(|(k, insn)| {
+        insn.dest().map(|name| ReachingDefinition {
+            name,
+            block: block.idx,
+            instr: Some(k),
+        })
+    })
+}
+
+/// Definitions of the arguments of a function
+fn arg_defs(func: &BBFunction) -> impl Iterator<Item = ReachingDefinition> + Clone {
+    func.args
+        .iter()
+        .map(|arg| ReachingDefinition::entry(arg.name.clone()))
+}
+
 #[derive(Default)]
 pub struct ReachingDefs;
 
@@ -21,13 +79,7 @@ impl DataflowSpec for ReachingDefs {
     type Val = HashSet<ReachingDefinition>;
 
     fn init(&self, func: &BBFunction) -> HashSet<ReachingDefinition> {
-        func.args
-            .iter()
-            .map(|arg| ReachingDefinition {
-                block: 0,
-                name: arg.name.clone(),
-            })
-            .collect()
+        arg_defs(func).collect()
     }
 
     fn meet(&self, in_vals: &[HashSet<ReachingDefinition>]) -> HashSet<ReachingDefinition> {
@@ -51,11 +103,129 @@ impl DataflowSpec for ReachingDefs {
             .collect();
 
         // Add ReachingDefinitions defined in the block
-        out_vals.extend(defines.into_iter().map(|name| ReachingDefinition {
-            name,
-            block: block.idx,
-        }));
+        out_vals.extend(block_defs(block));
+
+        out_vals
+    }
+
+    fn transfer_instr(
+        &self,
+        block: &BasicBlock,
+        k: usize,
+        in_val: &HashSet<ReachingDefinition>,
+    ) -> HashSet<ReachingDefinition> {
+        // The block holds just the instruction, so its definition is placed at
+        // the index of the instruction in the original block
+        let mut out_vals = in_val.clone();
+        for mut def in instr_defs(block) {
+            out_vals.retain(|other| other.name != def.name);
+            def.instr = Some(k);
+            out_vals.insert(def);
+        }
+        out_vals
+    }
+
+    fn as_gen_kill(&self) -> Option<&dyn GenKillSpec<Val = Self::Val>> {
+        Some(self)
+    }
+}
+
+impl GenKillSpec for ReachingDefs {
+    fn gen_kill(&self, func: &BBFunction) -> Vec<GenKill<HashSet<ReachingDefinition>>> {
+        // Every definition in the function, by name
+        let mut defs: HashMap<String, Vec<ReachingDefinition>> = HashMap::new();
+        for def in arg_defs(func).chain(func.blocks.iter().flat_map(block_defs)) {
+            defs.entry(def.name.clone()).or_default().push(def);
+        }
+
+        func.blocks
+            .iter()
+            .map(|block| {
+                let defines: HashSet<_> = block.iter().filter_map(|insn| insn.dest()).collect();
+
+                // Every definition of a name defined in this block is killed
+                let kill_set = defines
+                    .iter()
+                    .flat_map(|name| defs[name].iter().cloned())
+                    .collect();
+                let gen_set = block_defs(block).collect();
+
+                GenKill { gen_set, kill_set }
+            })
+            .collect()
+    }
+
+    fn apply(
+        &self,
+        gen_kill: &GenKill<HashSet<ReachingDefinition>>,
+        val: &mut HashSet<ReachingDefinition>,
+    ) {
+        val.retain(|def| !gen_kill.kill_set.contains(def));
+        val.extend(gen_kill.gen_set.iter().cloned());
+    }
+}
+
+#[derive(Default)]
+/// Reaching definitions, with sets stored as bit vectors over the function's
+/// definitions
+pub struct ReachingDefsBv {
+    universes: UniverseCache<ReachingDefinition>,
+}
+
+impl DataflowSpec for ReachingDefsBv {
+    type Val = BitSet<ReachingDefinition>;
+
+    fn init(&self, func: &BBFunction) -> BitSet<ReachingDefinition> {
+        let args = arg_defs(func);
+
+        // Definitions that are overwritten within their block are included,
+        // so that every instruction can be replayed on its own
+        let universe = self.universes.get(func, || {
+            Universe::new(args.clone().chain(func.blocks.iter().flat_map(instr_defs)))
+        });
+
+        let mut init = BitSet::empty(universe);
+        for def in args {
+            init.insert(def);
+        }
+        init
+    }
+
+    fn meet(&self, in_vals: &[BitSet<ReachingDefinition>]) -> BitSet<ReachingDefinition> {
+        BitSet::union(in_vals)
+    }
+
+    fn transfer(
+        &self,
+        block: &BasicBlock,
+        in_val: &BitSet<ReachingDefinition>,
+    ) -> BitSet<ReachingDefinition> {
+        // Set of defined names in this block
+        let defines: HashSet<_> = block.iter().filter_map(|insn| insn.dest()).collect();
 
+        // Kill all ReachingDefinitions in in_vals that write to this name
+        let mut out_vals = in_val.clone();
+        out_vals.retain(|def| !defines.contains(&def.name));
+
+        // Add ReachingDefinitions defined in the block
+        for def in block_defs(block) {
+            out_vals.insert(def);
+        }
+
+        out_vals
+    }
+    fn transfer_instr(
+        &self,
+        block: &BasicBlock,
+        k: usize,
+        in_val: &BitSet<ReachingDefinition>,
+    ) -> BitSet<ReachingDefinition> {
+        let mut out_vals = in_val.clone();
+        for mut def in instr_defs(block) {
+            out_vals.retain(|other| other.name != def.name);
+            def.instr = Some(k);
+            out_vals.insert(def);
+        }
         out_vals
     }
 }
@@ -0,0 +1,119 @@
+use crate::const_prop::{ConstProp, Value, branch_condition};
+use bril_utils::{BBFunction, BasicBlock, Dataflow, HashableLiteral, bril_rs::Literal};
+use itertools::Itertools;
+use serde::Serialize;
+use std::{collections::HashMap, fmt::Debug};
+use utils::DataflowSpec;
+
+#[derive(Clone, PartialEq, Eq)]
+pub enum SccpVal {
+    /// No executable path reaches this point
+    Unreachable,
+    /// Constants of a reachable point
+    Reachable(HashMap<String, Value>),
+}
+
+impl Debug for SccpVal {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SccpVal::Unreachable => write!(f, "unreachable"),
+            SccpVal::Reachable(vals) => vals.fmt(f),
+        }
+    }
+}
+
+impl Serialize for SccpVal {
+    /// Unreachable points are serialized as "unreachable"
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        match self {
+            SccpVal::Unreachable => serializer.serialize_str("unreachable"),
+            SccpVal::Reachable(vals) => vals.serialize(serializer),
+        }
+    }
+}
+
+#[derive(Default)]
+/// Sparse conditional constant propagation. Values only flow along edges that
+/// can be executed given the constants known at the end of their source block,
+/// so blocks behind constant branches are found to be unreachable.
+pub struct Sccp;
+
+impl Sccp {
+    /// The blocks found to be unreachable
+    pub fn unreachable(dataflow: &Dataflow<SccpVal>) -> Vec<usize> {
+        (0..dataflow.cfg.len())
+            .filter(|&i| dataflow.in_vals[i] == SccpVal::Unreachable)
+            .collect()
+    }
+}
+
+/// Whether the edge from block `from` to block `to` can be taken, given the
+/// constants at the end of `from`
+fn executable(func: &BBFunction, from: usize, to: usize, vals: &HashMap<String, Value>) -> bool {
+    match branch_condition(func, from, to) {
+        Some((cond, taken)) => match vals.get(cond) {
+            Some(Value::Const(c)) => *c == HashableLiteral::from(Literal::Bool(taken)),
+            _ => true,
+        },
+        None => true,
+    }
+}
+
+impl DataflowSpec for Sccp {
+    type Val = SccpVal;
+
+    fn entry(&self, func: &BBFunction) -> SccpVal {
+        SccpVal::Reachable(ConstProp.entry(func))
+    }
+
+    fn init(&self, _: &BBFunction) -> SccpVal {
+        SccpVal::Unreachable
+    }
+
+    fn meet(&self, in_vals: &[SccpVal]) -> SccpVal {
+        let reachable = in_vals
+            .iter()
+            .filter_map(|val| match val {
+                SccpVal::Unreachable => None,
+                SccpVal::Reachable(vals) => Some(vals.clone()),
+            })
+            .collect_vec();
+
+        if reachable.is_empty() {
+            SccpVal::Unreachable
+        } else {
+            SccpVal::Reachable(ConstProp.meet(&reachable))
+        }
+    }
+
+    fn meet_edges(
+        &self,
+        func: &BBFunction,
+        to: usize,
+        preds: &[usize],
+        in_vals: &[SccpVal],
+    ) -> SccpVal {
+        // Only meet values coming along executable edges
+        let executed = preds
+            .iter()
+            .zip(in_vals)
+            .filter(|&(&from, val)| match val {
+                SccpVal::Unreachable => false,
+                SccpVal::Reachable(vals) => executable(func, from, to, vals),
+            })
+            .map(|(_, val)| val.clone())
+            .collect_vec();
+
+        self.meet(&executed)
+    }
+
+    fn transfer(&self, block: &BasicBlock, in_val: &SccpVal) -> SccpVal {
+        match in_val {
+            SccpVal::Unreachable => SccpVal::Unreachable,
+            SccpVal::Reachable(vals) => SccpVal::Reachable(ConstProp.transfer(block, vals)),
+        }
+    }
+}
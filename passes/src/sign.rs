@@ -0,0 +1,91 @@
+use crate::int_domain::{self, IntDomain, IntState};
+use bril_utils::{BBFunction, BasicBlock, bril_rs::ValueOps};
+use serde::Serialize;
+use utils::DataflowSpec;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+/// The sign of an integer
+pub enum SignVal {
+    /// No value, e.g. the result of a division by zero
+    Bottom,
+    Neg,
+    Zero,
+    Pos,
+    Top,
+}
+
+impl IntDomain for SignVal {
+    fn top() -> Self {
+        SignVal::Top
+    }
+
+    fn join(self, other: Self) -> Self {
+        match (self, other) {
+            (SignVal::Bottom, x) | (x, SignVal::Bottom) => x,
+            (x, y) if x == y => x,
+            _ => SignVal::Top,
+        }
+    }
+
+    fn constant(a: i64) -> Self {
+        match a.signum() {
+            -1 => SignVal::Neg,
+            0 => SignVal::Zero,
+            _ => SignVal::Pos,
+        }
+    }
+
+    /// Bril integers wrap around at 64 bits, so any sum or product of two
+    /// nonzero values could have any sign, e.g. `pos + pos` or `neg * neg` can
+    /// be negative or zero
+    fn arith(op: ValueOps, a: Self, b: Self) -> Self {
+        use SignVal::*;
+
+        match (op, a, b) {
+            (_, Bottom, _) | (_, _, Bottom) => Bottom,
+            (ValueOps::Add, Zero, x) | (ValueOps::Add, x, Zero) => x,
+            (ValueOps::Sub, x, Zero) => x,
+            // Negating a negative value overflows for the smallest integer
+            (ValueOps::Sub, Zero, Pos) => Neg,
+            (ValueOps::Mul, Zero, _) | (ValueOps::Mul, _, Zero) => Zero,
+            // Division by zero fails
+            (ValueOps::Div, _, Zero) => Bottom,
+            (ValueOps::Div, Zero, _) => Zero,
+            // Anything else can overflow, and truncation can round towards zero
+            _ => Top,
+        }
+    }
+
+    fn contains(self, a: i64) -> bool {
+        match self {
+            SignVal::Bottom => false,
+            SignVal::Top => true,
+            sign => sign == Self::constant(a),
+        }
+    }
+}
+
+#[derive(Default)]
+/// The sign of every integer variable
+pub struct Sign;
+
+impl DataflowSpec for Sign {
+    type Val = IntState<SignVal>;
+
+    fn entry(&self, func: &BBFunction) -> Self::Val {
+        int_domain::entry(func)
+    }
+
+    fn init(&self, _: &BBFunction) -> Self::Val {
+        int_domain::init()
+    }
+
+    fn meet(&self, in_vals: &[Self::Val]) -> Self::Val {
+        int_domain::meet(in_vals)
+    }
+
+    fn transfer(&self, block: &BasicBlock, in_val: &Self::Val) -> Self::Val {
+        int_domain::transfer(block, in_val)
+    }
+}
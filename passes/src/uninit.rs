@@ -0,0 +1,157 @@
+use crate::reaching_defs::{ReachingDefinition, ReachingDefs};
+use bril_utils::{BBFunction, BasicBlock, Dataflow, InstrExt, bril_rs::Instruction};
+use std::{collections::HashSet, fmt::Display};
+use utils::{DataflowSpec, block_label};
+
+#[derive(Default)]
+/// Reaching definitions where every variable that is not an argument also has
+/// a placeholder definition on entry to the function. A placeholder reaching a
+/// use means there is a path on which the variable is never defined.
+pub struct UninitDefs;
+
+impl DataflowSpec for UninitDefs {
+    type Val = HashSet<ReachingDefinition>;
+
+    fn entry(&self, func: &BBFunction) -> HashSet<ReachingDefinition> {
+        let args: HashSet<_> = func.args.iter().map(|arg| &arg.name).collect();
+        let mut val = ReachingDefs.init(func);
+
+        // Every variable mentioned in the function is undefined on entry
+        for insn in func.blocks.iter().flat_map(|block| block.iter()) {
+            for name in insn
+                .dest()
+                .into_iter()
+                .chain(insn.args().into_iter().flatten())
+            {
+                if !args.contains(&name) {
+                    val.insert(ReachingDefinition::entry(name));
+                }
+            }
+        }
+
+        val
+    }
+
+    fn init(&self, func: &BBFunction) -> HashSet<ReachingDefinition> {
+        ReachingDefs.init(func)
+    }
+
+    fn meet(&self, in_vals: &[HashSet<ReachingDefinition>]) -> HashSet<ReachingDefinition> {
+        ReachingDefs.meet(in_vals)
+    }
+
+    fn transfer(
+        &self,
+        block: &BasicBlock,
+        in_val: &HashSet<ReachingDefinition>,
+    ) -> HashSet<ReachingDefinition> {
+        ReachingDefs.transfer(block, in_val)
+    }
+
+    fn transfer_instr(
+        &self,
+        block: &BasicBlock,
+        k: usize,
+        in_val: &HashSet<ReachingDefinition>,
+    ) -> HashSet<ReachingDefinition> {
+        ReachingDefs.transfer_instr(block, k, in_val)
+    }
+}
+
+/// A use of a variable that may not have been defined
+pub struct UninitUse {
+    pub func: String,
+    pub block: usize,
+    pub label: Option<String>,
+    pub instr: Instruction,
+    pub name: String,
+    /// The definitions of the variable that reach the use. If this is empty,
+    /// the variable is never defined before the use.
+    pub defs: Vec<ReachingDefinition>,
+}
+
+impl UninitUse {
+    /// Find every use of a variable in a function that is undefined on at least
+    /// one path from the entry, given the results of `UninitDefs`
+    pub fn find(dataflow: &Dataflow<HashSet<ReachingDefinition>>) -> Vec<Self> {
+        let func = dataflow.cfg.func();
+        let args: HashSet<_> = func.args.iter().map(|arg| &arg.name).collect();
+        let mut uses = Vec::new();
+
+        // Blocks that are never reached from the entry cannot use anything
+        let mut reached = vec![false; dataflow.cfg.len()];
+        let mut stack = (0..dataflow.cfg.len())
+            .filter(|&i| func.get(i).is_entry())
+            .collect::<Vec<_>>();
+        while let Some(i) = stack.pop() {
+            if !reached[i] {
+                reached[i] = true;
+                stack.extend(dataflow.cfg.succs(i));
+            }
+        }
+
+        for i in 0..dataflow.cfg.len() {
+            if !reached[i] {
+                continue;
+            }
+            let block = func.get(i);
+
+            // Variables defined earlier in the block are always initialized
+            let mut defined = HashSet::new();
+
+            for insn in block.iter() {
+                for name in insn.args().into_iter().flatten() {
+                    if defined.contains(&name) || args.contains(&name) {
+                        continue;
+                    }
+
+                    let reaching = dataflow.in_vals[i]
+                        .iter()
+                        .filter(|def| def.name == name)
+                        .collect::<Vec<_>>();
+
+                    if reaching.iter().any(|def| def.instr.is_none()) {
+                        uses.push(UninitUse {
+                            func: func.name.clone(),
+                            block: i,
+                            label: block_label(block).map(str::to_string),
+                            instr: insn.clone(),
+                            defs: reaching
+                                .into_iter()
+                                .filter(|def| def.instr.is_some())
+                                .cloned()
+                                .collect(),
+                            name,
+                        });
+                    }
+                }
+
+                if let Some(dest) = insn.dest() {
+                    defined.insert(dest);
+                }
+            }
+        }
+
+        uses
+    }
+}
+
+impl Display for UninitUse {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "@{}: ", self.func)?;
+        match &self.label {
+            Some(label) => write!(f, ".{}", label)?,
+            None => write!(f, "block {}", self.block)?,
+        }
+
+        if self.defs.is_empty() {
+            write!(f, ": {} is uninitialized in `{}`", self.name, self.instr)
+        } else {
+            write!(
+                f,
+                ": {} may be uninitialized in `{}` (defined at {:?})",
+                self.name, self.instr, self.defs
+            )
+        }
+    }
+}
@@ -0,0 +1,51 @@
+use crate::available_expr::{Expr, Set};
+use bril_utils::{BBFunction, BasicBlock, InstrExt};
+use utils::DataflowSpec;
+
+#[derive(Default)]
+/// Expressions that are evaluated on every path from a program point before
+/// any of their arguments are redefined
+pub struct VeryBusyExprs;
+
+impl DataflowSpec for VeryBusyExprs {
+    type Val = Set;
+
+    fn reversed(&self) -> bool {
+        true
+    }
+
+    fn entry(&self, _: &BBFunction) -> Set {
+        // Nothing is evaluated after the function returns
+        Set::empty()
+    }
+
+    fn init(&self, _: &BBFunction) -> Set {
+        Set::full()
+    }
+
+    fn meet(&self, in_vals: &[Set]) -> Set {
+        // Set intersection
+        in_vals
+            .iter()
+            .fold(Set::full(), |acc, val| acc.intersect(val))
+    }
+
+    fn transfer(&self, block: &BasicBlock, in_val: &Set) -> Set {
+        let mut out_vals = in_val.clone();
+
+        for instr in block.iter().rev() {
+            // The destination is written after the arguments are read
+            if let Some(dest) = instr.dest() {
+                if let Set::Finite(set) = &mut out_vals {
+                    set.retain(|expr| !expr.contains(&dest));
+                }
+            }
+
+            if let Some(e) = Expr::new(instr) {
+                out_vals.insert(e);
+            }
+        }
+
+        out_vals
+    }
+}
@@ -0,0 +1,97 @@
+use crate::{DataflowSpec, GenKill, GenKillSpec};
+use bril_utils::CFG;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/// Transfer functions of the blocks of a CFG. If the pass has a gen/kill form,
+/// the gen and kill sets of every block are computed up front and reused on
+/// every visit. Visits to each block are counted to decide when to widen, which
+/// only happens at loop heads.
+pub struct BlockTransfer<'a, Pass: DataflowSpec> {
+    pass: &'a Pass,
+    cfg: &'a CFG,
+    gen_kill: Option<(
+        &'a dyn GenKillSpec<Val = Pass::Val>,
+        Vec<GenKill<Pass::Val>>,
+    )>,
+    visits: Vec<AtomicUsize>,
+    heads: Vec<bool>,
+}
+
+impl<'a, Pass: DataflowSpec> BlockTransfer<'a, Pass> {
+    pub fn new(pass: &'a Pass, cfg: &'a CFG) -> Self {
+        let gen_kill = pass
+            .as_gen_kill()
+            .map(|spec| (spec, spec.gen_kill(cfg.func())));
+
+        Self {
+            pass,
+            cfg,
+            gen_kill,
+            visits: (0..cfg.len()).map(|_| AtomicUsize::new(0)).collect(),
+            heads: loop_heads(cfg),
+        }
+    }
+
+    /// Apply the transfer function of block `i`. Once a loop head has been
+    /// visited more often than the pass's widening delay, the result is
+    /// widened with the previous out value `old_val`.
+    pub fn apply(&self, i: usize, in_val: &Pass::Val, old_val: &Pass::Val) -> Pass::Val {
+        let new_val = match &self.gen_kill {
+            Some((spec, sets)) => {
+                let mut val = in_val.clone();
+                spec.apply(&sets[i], &mut val);
+                val
+            }
+            None => self.pass.transfer(self.cfg.func().get(i), in_val),
+        };
+
+        // At most one thread processes a block at a time
+        let visits = self.visits[i].fetch_add(1, Ordering::Relaxed) + 1;
+        if self.heads[i] && visits > self.pass.widen_delay() {
+            self.pass.widen(old_val, new_val)
+        } else {
+            new_val
+        }
+    }
+}
+
+/// Find the loop heads of a CFG: the targets of back edges of a depth-first
+/// search. The search starts from the same roots as a reverse postorder, so
+/// every cycle passes through at least one head, and every executor widens at
+/// the same blocks.
+pub fn loop_heads(cfg: &CFG) -> Vec<bool> {
+    let n = cfg.len();
+    let roots = (0..n)
+        .filter(|&i| cfg.func().get(i).is_entry() || cfg.preds(i).is_empty())
+        .chain(0..n);
+
+    let mut visited = vec![false; n];
+    let mut on_stack = vec![false; n];
+    let mut heads = vec![false; n];
+
+    for root in roots {
+        if visited[root] {
+            continue;
+        }
+        visited[root] = true;
+        on_stack[root] = true;
+
+        let mut stack = vec![(root, cfg.succs(root).into_iter())];
+        while let Some((i, succs)) = stack.last_mut() {
+            if let Some(j) = succs.next() {
+                if on_stack[j] {
+                    heads[j] = true;
+                } else if !visited[j] {
+                    visited[j] = true;
+                    on_stack[j] = true;
+                    stack.push((j, cfg.succs(j).into_iter()));
+                }
+            } else {
+                on_stack[*i] = false;
+                stack.pop();
+            }
+        }
+    }
+
+    heads
+}
@@ -1,7 +1,6 @@
 use crate::DataflowSpec;
 use bril_utils::{CFG, CanonicalizeLiterals, Dataflow, Pass, bril_rs::Program};
 use bril2json::parse_abstract_program_from_read;
-use itertools::Itertools;
 use std::{
     time::{Duration, Instant},
 };
@@ -10,6 +9,63 @@ use rayon::prelude::*;
 pub struct PassTiming {
     pub loadtime: Duration,
     pub runtime: Duration,
+    /// Work counters for each function, in program order
+    pub stats: Vec<DataflowStats>,
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+/// Counters describing the work an executor did on a single function
+pub struct DataflowStats {
+    /// Number of calls to the transfer function
+    pub transfers: usize,
+    /// Number of calls to the meet function
+    pub meets: usize,
+    /// Number of blocks added to the worklist, including the initial blocks
+    pub pushes: usize,
+    /// Number of synchronous rounds (batches) the executor went through
+    pub rounds: usize,
+    /// Maximum number of blocks that were pending at the same time
+    pub max_width: usize,
+    /// Number of times a block was processed again after its first visit
+    pub reprocessed: usize,
+    /// The blocks processed in each round, for executors that work in
+    /// synchronous rounds and were asked to record them
+    pub batches: Vec<Vec<usize>>,
+}
+
+impl DataflowStats {
+    /// Record that `width` blocks are currently pending
+    pub fn observe_width(&mut self, width: usize) {
+        self.max_width = self.max_width.max(width);
+    }
+}
+
+impl std::iter::Sum for DataflowStats {
+    /// Combine the counters of multiple functions. Maximum widths are combined
+    /// by taking the maximum, every other counter is added. Batches refer to
+    /// the blocks of a single function, so they are dropped.
+    fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
+        iter.fold(Self::default(), |acc, s| Self {
+            transfers: acc.transfers + s.transfers,
+            meets: acc.meets + s.meets,
+            pushes: acc.pushes + s.pushes,
+            rounds: acc.rounds + s.rounds,
+            max_width: acc.max_width.max(s.max_width),
+            reprocessed: acc.reprocessed + s.reprocessed,
+            batches: Vec::new(),
+        })
+    }
+}
+
+/// Parse a program in Bril text form, with canonical literals
+pub fn load_program<R: std::io::Read>(input: R) -> Program {
+    // Read stdin and parse it into a Program using serde
+    let prog: Program = parse_abstract_program_from_read(input, false, false, None)
+        .try_into()
+        .unwrap();
+
+    // Perform CanonicalizeLiterals always just to make sure things are canonical
+    CanonicalizeLiterals.run(prog)
 }
 
 pub trait DataflowExecutor<Pass>
@@ -26,13 +82,7 @@ where
     ) -> (PassTiming, Vec<Dataflow<Pass::Val>>) {
         let start = Instant::now();
 
-        // Read stdin and parse it into a Program using serde
-        let prog: Program = parse_abstract_program_from_read(input, false, false, None)
-            .try_into()
-            .unwrap();
-
-        // Perform CanonicalizeLiterals always just to make sure things are canonical
-        let mut prog = CanonicalizeLiterals.run(prog);
+        let mut prog = load_program(input);
 
         let loadtime = start.elapsed();
 
@@ -57,10 +107,13 @@ where
             //             });
             //         })
             // });
-            let results: Vec<_> = std::mem::take(&mut prog.functions).into_par_iter().map(|f| {
-                let cfg = CFG::from(f.clone());
-                self.cfg(pass, cfg)
-            }).collect();
+            let (results, stats): (Vec<_>, Vec<_>) = std::mem::take(&mut prog.functions)
+                .into_par_iter()
+                .map(|f| {
+                    let cfg = CFG::from(f.clone());
+                    self.cfg(pass, cfg)
+                })
+                .unzip();
 
             // let results = Arc::try_unwrap(shared_results)
             //     .unwrap()
@@ -74,21 +127,36 @@ where
             let runtime = start.elapsed();
 
             // todo!()
-            (PassTiming { loadtime, runtime }, results)
+            (
+                PassTiming {
+                    loadtime,
+                    runtime,
+                    stats,
+                },
+                results,
+            )
         } else {
             let start = Instant::now();
 
-            let results = prog
+            let (results, stats): (Vec<_>, Vec<_>) = prog
                 .functions
                 .iter()
                 .map(|f| self.cfg(pass, CFG::from(f.clone())))
-                .collect_vec();
+                .unzip();
 
             let runtime = start.elapsed();
 
-            (PassTiming { loadtime, runtime }, results)
+            (
+                PassTiming {
+                    loadtime,
+                    runtime,
+                    stats,
+                },
+                results,
+            )
         }
     }
 
-    fn cfg(&self, pass: &Pass, cfg: CFG) -> Dataflow<Pass::Val>;
+    /// Run the dataflow pass on a single CFG, counting the work done
+    fn cfg(&self, pass: &Pass, cfg: CFG) -> (Dataflow<Pass::Val>, DataflowStats);
 }
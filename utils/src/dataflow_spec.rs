@@ -1,9 +1,10 @@
 use bril_utils::{BBFunction, BasicBlock};
+use serde::Serialize;
 use std::fmt::Debug;
 
 /// Specifies a dataflow pass to be executed by a DataflowExecutor
 pub trait DataflowSpec {
-    type Val: Eq + Clone + Debug + Send + Sync + Sized;
+    type Val: Eq + Clone + Debug + Serialize + Send + Sync + Sized;
 
     /// Whether this dataflow pass is reversed
     fn reversed(&self) -> bool {
@@ -21,11 +22,86 @@ pub trait DataflowSpec {
     /// Meet function
     fn meet(&self, in_vals: &[Self::Val]) -> Self::Val;
 
+    /// Refine the out value of block `from` along its edge to block `to`,
+    /// before it is passed to the meet of `to`. Blocks are indices in the CFG
+    /// the pass runs on, so for reversed passes `from` is a successor of `to`
+    /// in the program. Defaults to the identity.
+    fn edge_transfer(
+        &self,
+        _func: &BBFunction,
+        _from: usize,
+        _to: usize,
+        val: Self::Val,
+    ) -> Self::Val {
+        val
+    }
+
+    /// Meet function for the in value of block `to`, which also receives the
+    /// index of the predecessor each value comes from. `preds` and `in_vals`
+    /// have the same length. Defaults to `meet`.
+    fn meet_edges(
+        &self,
+        _func: &BBFunction,
+        _to: usize,
+        _preds: &[usize],
+        in_vals: &[Self::Val],
+    ) -> Self::Val {
+        self.meet(in_vals)
+    }
+
     /// Transfer function
     fn transfer(&self, block: &BasicBlock, in_val: &Self::Val) -> Self::Val;
 
+    /// Transfer function of a single instruction, used to replay a block one
+    /// instruction at a time. `block` holds only the instruction, which is at
+    /// index `k` of the original block. Defaults to `transfer`, which is right
+    /// for passes whose facts do not refer to positions in a block.
+    fn transfer_instr(&self, block: &BasicBlock, _k: usize, in_val: &Self::Val) -> Self::Val {
+        self.transfer(block, in_val)
+    }
+
     /// Transfer function for the exit block
     fn finish(&self, _func: &BBFunction, exit_val: Self::Val) -> Self::Val {
         exit_val
     }
+
+    /// Widening operator, applied to the new out value of a loop head once it
+    /// has been visited more than `widen_delay` times. Every cycle of the CFG
+    /// passes through a loop head, so widening there is enough to terminate.
+    /// The result must be an upper bound of both values, and repeated
+    /// widening must eventually stop changing the value.
+    fn widen(&self, _old: &Self::Val, new: Self::Val) -> Self::Val {
+        new
+    }
+
+    /// Number of visits to a loop head before its out value is widened
+    fn widen_delay(&self) -> usize {
+        usize::MAX
+    }
+
+    /// The gen/kill form of this pass, if it has one. Passes implementing
+    /// GenKillSpec should return `Some(self)` so executors can use it.
+    fn as_gen_kill(&self) -> Option<&dyn GenKillSpec<Val = Self::Val>> {
+        None
+    }
+}
+
+/// Gen and kill sets of a single block
+pub struct GenKill<Val> {
+    /// Values generated by the block
+    pub gen_set: Val,
+    /// Values killed by the block
+    pub kill_set: Val,
+}
+
+/// A dataflow pass whose transfer function is `out = gen ∪ (in − kill)`, with
+/// gen and kill sets that only depend on the block. Executors compute the sets
+/// once per function instead of rescanning instructions on every visit.
+pub trait GenKillSpec: DataflowSpec + Send + Sync {
+    /// Compute the gen and kill sets of every block of a function, in order
+    fn gen_kill(&self, func: &BBFunction) -> Vec<GenKill<Self::Val>>;
+
+    /// Apply precomputed gen and kill sets to a value in place. This must agree
+    /// with `transfer` on the block the sets were computed for.
+    fn apply(&self, gen_kill: &GenKill<Self::Val>, val: &mut Self::Val);
 }
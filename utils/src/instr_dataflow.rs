@@ -0,0 +1,53 @@
+use crate::DataflowSpec;
+use bril_utils::Dataflow;
+
+/// Instruction-level results on top of a finished block-level solution
+pub trait InstrDataflow<Val> {
+    /// Replay the transfer function of `pass` one instruction at a time. For
+    /// every block, returns the facts at each program point in program order:
+    /// entry `k` holds the fact just before instruction `k`, and the last entry
+    /// holds the fact after the last instruction. This holds for reversed
+    /// passes as well, where facts flow from the last entry to the first.
+    ///
+    /// Each instruction goes through `transfer_instr` along with its index in
+    /// the block, so the replay agrees with the block-level solution as long
+    /// as the transfer function of a block is the composition of those of its
+    /// instructions.
+    fn instr_vals<Pass>(&self, pass: &Pass) -> Vec<Vec<Val>>
+    where
+        Pass: DataflowSpec<Val = Val>;
+}
+
+impl<Val: Clone> InstrDataflow<Val> for Dataflow<Val> {
+    fn instr_vals<Pass>(&self, pass: &Pass) -> Vec<Vec<Val>>
+    where
+        Pass: DataflowSpec<Val = Val>,
+    {
+        (0..self.cfg.len())
+            .map(|i| {
+                let block = self.cfg.func().get(i);
+                let instrs: Vec<_> = block.iter().enumerate().collect();
+
+                // A copy of the block that holds a single instruction at a time
+                let mut single = block.clone();
+                let mut vals = vec![self.in_vals[i].clone()];
+
+                // Reversed passes walk the block backwards from its end
+                if pass.reversed() {
+                    for (k, instr) in instrs.into_iter().rev() {
+                        single.instrs = vec![instr.clone()];
+                        vals.push(pass.transfer_instr(&single, k, vals.last().unwrap()));
+                    }
+                    vals.reverse();
+                } else {
+                    for (k, instr) in instrs {
+                        single.instrs = vec![instr.clone()];
+                        vals.push(pass.transfer_instr(&single, k, vals.last().unwrap()));
+                    }
+                }
+
+                vals
+            })
+            .collect()
+    }
+}
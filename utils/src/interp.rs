@@ -0,0 +1,289 @@
+use bril_utils::{
+    BBFunction, CFG,
+    bril_rs::{EffectOps, Instruction, Literal, Program, Type, ValueOps},
+};
+use itertools::Itertools;
+use std::collections::HashMap;
+
+/// Values of the variables of a running function
+pub type Env = HashMap<String, Literal>;
+
+/// Hooks called while a program runs. Hooks of a function are called between
+/// its `call` and `ret`, and calls nest.
+pub trait Observer {
+    /// A call of `func` starts
+    fn call(&mut self, _func: &BBFunction) {}
+
+    /// Block `block` of `func` is entered
+    fn enter(&mut self, _func: &BBFunction, _block: usize, _env: &Env) {}
+
+    /// Instruction `instr` of block `block` is about to run
+    fn instr(&mut self, _func: &BBFunction, _block: usize, _instr: usize, _env: &Env) {}
+
+    /// Block `block` of `func` is left, after its last instruction ran. This is
+    /// not called when the program stops with an error.
+    fn exit(&mut self, _func: &BBFunction, _block: usize, _env: &Env) {}
+
+    /// The current call of `func` returns
+    fn ret(&mut self, _func: &BBFunction) {}
+}
+
+impl Observer for () {}
+
+/// Where control goes after a block
+enum Flow {
+    Goto(String),
+    Return(Option<Literal>),
+}
+
+/// Interpreter for core Bril, running each function over its CFG
+pub struct Interpreter {
+    cfgs: HashMap<String, CFG>,
+    /// Index of the block of each label, per function
+    labels: HashMap<String, HashMap<String, usize>>,
+    /// Maximum number of instructions to run
+    limit: usize,
+}
+
+/// The state of a single run
+struct Run<'a, O: Observer> {
+    interp: &'a Interpreter,
+    observer: &'a mut O,
+    output: String,
+    steps: usize,
+}
+
+impl Interpreter {
+    pub fn new(prog: &Program) -> Self {
+        let cfgs: HashMap<_, _> = prog
+            .functions
+            .iter()
+            .map(|f| (f.name.clone(), CFG::from(f.clone())))
+            .collect();
+
+        let labels = cfgs
+            .iter()
+            .map(|(name, cfg)| {
+                let labels = (0..cfg.len())
+                    .filter_map(|i| cfg.func().get(i).label.clone().map(|l| (l, i)))
+                    .collect();
+                (name.clone(), labels)
+            })
+            .collect();
+
+        Self {
+            cfgs,
+            labels,
+            limit: 10_000_000,
+        }
+    }
+
+    /// Stop runs with an error after `limit` instructions
+    pub fn with_limit(mut self, limit: usize) -> Self {
+        self.limit = limit;
+        self
+    }
+
+    /// Run `main` with the given arguments, in the textual form of `# ARGS:`
+    /// comments. Returns the printed output, or an error message.
+    pub fn run<O: Observer>(&self, args: &[String], observer: &mut O) -> Result<String, String> {
+        let main = self.cfgs.get("main").ok_or("No main function")?.func();
+        if main.args.len() != args.len() {
+            return Err(format!(
+                "main takes {} arguments, got {}",
+                main.args.len(),
+                args.len()
+            ));
+        }
+
+        let args = main
+            .args
+            .iter()
+            .zip(args)
+            .map(|(arg, s)| match arg.arg_type {
+                Type::Int => s.parse().map(Literal::Int).map_err(|_| s.clone()),
+                Type::Bool => s.parse().map(Literal::Bool).map_err(|_| s.clone()),
+                _ => Err(s.clone()),
+            })
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|s| format!("Bad argument {}", s))?;
+
+        let mut run = Run {
+            interp: self,
+            observer,
+            output: String::new(),
+            steps: 0,
+        };
+        run.call("main", args)?;
+        Ok(run.output)
+    }
+}
+
+/// The arguments in the `# ARGS:` comment of a benchmark, if any
+pub fn bench_args(source: &str) -> Vec<String> {
+    source
+        .lines()
+        .find_map(|line| line.trim().strip_prefix("# ARGS:"))
+        .map(|args| args.split_whitespace().map(str::to_string).collect())
+        .unwrap_or_default()
+}
+
+fn int(lit: &Literal) -> Result<i64, String> {
+    match lit {
+        Literal::Int(a) => Ok(*a),
+        _ => Err(format!("Expected an int, got {}", lit)),
+    }
+}
+
+fn boolean(lit: &Literal) -> Result<bool, String> {
+    match lit {
+        Literal::Bool(b) => Ok(*b),
+        _ => Err(format!("Expected a bool, got {}", lit)),
+    }
+}
+
+impl<O: Observer> Run<'_, O> {
+    fn get(env: &Env, name: &str) -> Result<Literal, String> {
+        env.get(name)
+            .cloned()
+            .ok_or_else(|| format!("Undefined variable {}", name))
+    }
+
+    fn call(&mut self, name: &str, args: Vec<Literal>) -> Result<Option<Literal>, String> {
+        let interp = self.interp;
+        let cfg = interp
+            .cfgs
+            .get(name)
+            .ok_or_else(|| format!("Undefined function {}", name))?;
+        let func = cfg.func();
+        let labels = &interp.labels[name];
+
+        let mut env: Env = func
+            .args
+            .iter()
+            .map(|arg| arg.name.clone())
+            .zip(args)
+            .collect();
+        let mut block = (0..cfg.len())
+            .find(|&i| func.get(i).is_entry())
+            .unwrap_or(0);
+
+        self.observer.call(func);
+        loop {
+            self.observer.enter(func, block, &env);
+
+            let mut flow = None;
+            for (k, instr) in func.get(block).iter().enumerate() {
+                self.steps += 1;
+                if self.steps > interp.limit {
+                    return Err(format!("Ran more than {} instructions", interp.limit));
+                }
+
+                self.observer.instr(func, block, k, &env);
+                flow = self.step(instr, &mut env)?;
+                if flow.is_some() {
+                    break;
+                }
+            }
+
+            self.observer.exit(func, block, &env);
+            block = match flow {
+                Some(Flow::Goto(label)) => *labels
+                    .get(&label)
+                    .ok_or_else(|| format!("Undefined label {}", label))?,
+                Some(Flow::Return(val)) => {
+                    self.observer.ret(func);
+                    return Ok(val);
+                }
+                // Fall through to the next block, or off the end of the function
+                None => match cfg.succs(block).into_iter().next() {
+                    Some(next) => next,
+                    None => {
+                        self.observer.ret(func);
+                        return Ok(None);
+                    }
+                },
+            };
+        }
+    }
+
+    /// Run a single instruction, returning where control goes if it is a
+    /// terminator
+    fn step(&mut self, instr: &Instruction, env: &mut Env) -> Result<Option<Flow>, String> {
+        match instr {
+            Instruction::Constant { dest, value, .. } => {
+                env.insert(dest.clone(), value.clone());
+            }
+            Instruction::Value {
+                dest,
+                op,
+                args,
+                funcs,
+                ..
+            } => {
+                let vals = args
+                    .iter()
+                    .map(|a| Self::get(env, a))
+                    .collect::<Result<Vec<_>, _>>()?;
+                let val = match op {
+                    ValueOps::Id => vals[0].clone(),
+                    ValueOps::Call => self
+                        .call(&funcs[0], vals)?
+                        .ok_or_else(|| format!("{} returned nothing", funcs[0]))?,
+                    ValueOps::Add => Literal::Int(int(&vals[0])?.wrapping_add(int(&vals[1])?)),
+                    ValueOps::Sub => Literal::Int(int(&vals[0])?.wrapping_sub(int(&vals[1])?)),
+                    ValueOps::Mul => Literal::Int(int(&vals[0])?.wrapping_mul(int(&vals[1])?)),
+                    ValueOps::Div => match int(&vals[1])? {
+                        0 => return Err("Division by zero".to_string()),
+                        b => Literal::Int(int(&vals[0])?.wrapping_div(b)),
+                    },
+                    ValueOps::Eq => Literal::Bool(int(&vals[0])? == int(&vals[1])?),
+                    ValueOps::Lt => Literal::Bool(int(&vals[0])? < int(&vals[1])?),
+                    ValueOps::Gt => Literal::Bool(int(&vals[0])? > int(&vals[1])?),
+                    ValueOps::Le => Literal::Bool(int(&vals[0])? <= int(&vals[1])?),
+                    ValueOps::Ge => Literal::Bool(int(&vals[0])? >= int(&vals[1])?),
+                    ValueOps::Not => Literal::Bool(!boolean(&vals[0])?),
+                    ValueOps::And => Literal::Bool(boolean(&vals[0])? && boolean(&vals[1])?),
+                    ValueOps::Or => Literal::Bool(boolean(&vals[0])? || boolean(&vals[1])?),
+                    _ => return Err(format!("Unsupported operation {}", op)),
+                };
+                env.insert(dest.clone(), val);
+            }
+            Instruction::Effect {
+                op,
+                args,
+                funcs,
+                labels,
+                ..
+            } => {
+                let vals = args
+                    .iter()
+                    .map(|a| Self::get(env, a))
+                    .collect::<Result<Vec<_>, _>>()?;
+                match op {
+                    EffectOps::Jump => return Ok(Some(Flow::Goto(labels[0].clone()))),
+                    EffectOps::Branch => {
+                        let taken = if boolean(&vals[0])? {
+                            &labels[0]
+                        } else {
+                            &labels[1]
+                        };
+                        return Ok(Some(Flow::Goto(taken.clone())));
+                    }
+                    EffectOps::Return => return Ok(Some(Flow::Return(vals.into_iter().next()))),
+                    EffectOps::Print => {
+                        self.output += &vals.iter().join(" ");
+                        self.output.push('\n');
+                    }
+                    EffectOps::Call => {
+                        self.call(&funcs[0], vals)?;
+                    }
+                    EffectOps::Nop => {}
+                    _ => return Err(format!("Unsupported operation {}", op)),
+                }
+            }
+        }
+
+        Ok(None)
+    }
+}
@@ -1,5 +1,15 @@
+mod block_transfer;
 mod dataflow_executor;
 mod dataflow_spec;
+mod instr_dataflow;
+mod interp;
+mod output;
+mod product;
 
-pub use dataflow_executor::{DataflowExecutor, PassTiming};
-pub use dataflow_spec::DataflowSpec;
+pub use block_transfer::{BlockTransfer, loop_heads};
+pub use dataflow_executor::{DataflowExecutor, DataflowStats, PassTiming, load_program};
+pub use dataflow_spec::{DataflowSpec, GenKill, GenKillSpec};
+pub use instr_dataflow::InstrDataflow;
+pub use interp::{Env, Interpreter, Observer, bench_args};
+pub use output::{BlockResult, FunctionResult, annotate, block_label, bril_function, dot_cluster};
+pub use product::Product;
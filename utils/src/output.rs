@@ -0,0 +1,186 @@
+use bril_utils::{
+    BBFunction, BasicBlock, Dataflow,
+    bril_rs::{Code, Function, Instruction},
+};
+use itertools::Itertools;
+use serde::Serialize;
+use std::fmt::Debug;
+
+/// The label of a block, if it has one
+pub fn block_label(block: &BasicBlock) -> Option<&str> {
+    block.label.as_deref()
+}
+
+/// Turn a function back into Bril, with the instructions of each block
+/// replaced by `instrs`. Blocks keep their order, so fallthrough is preserved.
+pub fn bril_function(func: &BBFunction, instrs: Vec<Vec<Instruction>>) -> Function {
+    let code = func
+        .blocks
+        .iter()
+        .zip(instrs)
+        .flat_map(|(block, instrs)| {
+            block
+                .label
+                .iter()
+                .map(|label| Code::Label {
+                    label: label.clone(),
+                    pos: None,
+                })
+                .chain(instrs.into_iter().map(Code::Instruction))
+                .collect::<Vec<_>>()
+        })
+        .collect();
+
+    Function {
+        name: func.name.clone(),
+        args: func.args.clone(),
+        return_type: func.return_type.clone(),
+        instrs: code,
+        pos: None,
+    }
+}
+
+#[derive(Serialize)]
+/// Serializable dataflow results of a single block
+pub struct BlockResult<'a, Val> {
+    pub index: usize,
+    pub label: Option<&'a str>,
+    #[serde(rename = "in")]
+    pub in_val: &'a Val,
+    #[serde(rename = "out")]
+    pub out_val: &'a Val,
+}
+
+#[derive(Serialize)]
+/// Serializable dataflow results of a single function. For reversed passes,
+/// the in value of a block holds at its end and the out value at its start.
+pub struct FunctionResult<'a, Val> {
+    pub name: String,
+    pub reversed: bool,
+    pub blocks: Vec<BlockResult<'a, Val>>,
+    pub exit: &'a Val,
+}
+
+impl<'a, Val> From<&'a Dataflow<Val>> for FunctionResult<'a, Val> {
+    fn from(dataflow: &'a Dataflow<Val>) -> Self {
+        let blocks = (0..dataflow.cfg.len())
+            .map(|i| BlockResult {
+                index: i,
+                label: block_label(dataflow.cfg.func().get(i)),
+                in_val: &dataflow.in_vals[i],
+                out_val: &dataflow.out_vals[i],
+            })
+            .collect();
+
+        Self {
+            name: dataflow.cfg.name().to_string(),
+            reversed: dataflow.cfg.reversed(),
+            blocks,
+            exit: &dataflow.exit_val,
+        }
+    }
+}
+
+/// Print the function in Bril text form, with the dataflow facts of each block
+/// as comments. Facts are written in program order: the fact above a block
+/// holds at its start and the fact below it holds at its end, which for
+/// reversed passes are the out and in values respectively.
+pub fn annotate<Val: Debug>(dataflow: &Dataflow<Val>) -> String {
+    let func = dataflow.cfg.func();
+    let mut out = format!("@{}", dataflow.cfg.name());
+
+    if !func.args.is_empty() {
+        out += &format!("({})", func.args.iter().join(", "));
+    }
+    if let Some(return_type) = &func.return_type {
+        out += &format!(": {}", return_type);
+    }
+    out += " {\n";
+
+    let (above, below) = if dataflow.cfg.reversed() {
+        (("out", &dataflow.out_vals), ("in", &dataflow.in_vals))
+    } else {
+        (("in", &dataflow.in_vals), ("out", &dataflow.out_vals))
+    };
+
+    for i in 0..dataflow.cfg.len() {
+        let block = func.get(i);
+        out += &format!("  # {}: {:?}\n", above.0, above.1[i]);
+        if let Some(label) = block_label(block) {
+            out += &format!(".{}:\n", label);
+        }
+        for instr in block.iter() {
+            out += &format!("  {}\n", instr);
+        }
+        out += &format!("  # {}: {:?}\n", below.0, below.1[i]);
+    }
+
+    out += &format!("  # exit: {:?}\n", dataflow.exit_val);
+    out += "}\n";
+    out
+}
+
+/// Escape a string for use inside a quoted DOT label, with every line left
+/// justified
+fn dot_escape(s: &str) -> String {
+    s.replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .lines()
+        .map(|line| format!("{}\\l", line))
+        .collect()
+}
+
+/// Render the CFG of a function as a DOT cluster, with the contents and facts
+/// of each block as its label. Edges follow the CFG the pass ran on, so they
+/// are flipped for reversed passes. Blocks in `highlight` are filled.
+pub fn dot_cluster<Val: Debug>(dataflow: &Dataflow<Val>, highlight: &[usize]) -> String {
+    let name = dataflow.cfg.name();
+    let func = dataflow.cfg.func();
+    let mut out = format!(
+        "  subgraph \"cluster_{}\" {{\n    label=\"@{}{}\";\n",
+        name,
+        name,
+        if dataflow.cfg.reversed() {
+            " (reversed)"
+        } else {
+            ""
+        }
+    );
+
+    for i in 0..dataflow.cfg.len() {
+        let block = func.get(i);
+        let mut label = match block_label(block) {
+            Some(l) => format!(".{}:\n", l),
+            None => format!("block {}\n", i),
+        };
+        for instr in block.iter() {
+            label += &format!("  {}\n", instr);
+        }
+        label += &format!(
+            "in: {:?}\nout: {:?}\n",
+            dataflow.in_vals[i], dataflow.out_vals[i]
+        );
+
+        let style = if highlight.contains(&i) {
+            ", style=filled, fillcolor=lightblue"
+        } else {
+            ""
+        };
+        out += &format!(
+            "    \"{}.{}\" [shape=box{}, label=\"{}\"];\n",
+            name,
+            i,
+            style,
+            dot_escape(&label)
+        );
+    }
+
+    for i in 0..dataflow.cfg.len() {
+        for j in dataflow.cfg.succs(i) {
+            out += &format!("    \"{}.{}\" -> \"{}.{}\";\n", name, i, name, j);
+        }
+    }
+
+    out += "  }\n";
+    out
+}
@@ -0,0 +1,106 @@
+use crate::DataflowSpec;
+use bril_utils::{BBFunction, BasicBlock};
+
+/// Runs two passes in the same fixpoint, with pairs of their values. Both
+/// passes must go in the same direction.
+pub struct Product<A, B> {
+    a: A,
+    b: B,
+}
+
+impl<A: DataflowSpec, B: DataflowSpec> Product<A, B> {
+    /// Combine two passes
+    pub fn new(a: A, b: B) -> Self {
+        assert_eq!(
+            a.reversed(),
+            b.reversed(),
+            "Cannot combine passes going in different directions"
+        );
+        Self { a, b }
+    }
+}
+
+impl<A: DataflowSpec + Default, B: DataflowSpec + Default> Default for Product<A, B> {
+    fn default() -> Self {
+        Self::new(A::default(), B::default())
+    }
+}
+
+impl<A: DataflowSpec, B: DataflowSpec> DataflowSpec for Product<A, B> {
+    type Val = (A::Val, B::Val);
+
+    fn reversed(&self) -> bool {
+        self.a.reversed()
+    }
+
+    fn entry(&self, func: &BBFunction) -> Self::Val {
+        (self.a.entry(func), self.b.entry(func))
+    }
+
+    fn init(&self, func: &BBFunction) -> Self::Val {
+        (self.a.init(func), self.b.init(func))
+    }
+
+    fn meet(&self, in_vals: &[Self::Val]) -> Self::Val {
+        let (a_vals, b_vals): (Vec<_>, Vec<_>) = in_vals.iter().cloned().unzip();
+        (self.a.meet(&a_vals), self.b.meet(&b_vals))
+    }
+
+    fn edge_transfer(
+        &self,
+        func: &BBFunction,
+        from: usize,
+        to: usize,
+        val: Self::Val,
+    ) -> Self::Val {
+        (
+            self.a.edge_transfer(func, from, to, val.0),
+            self.b.edge_transfer(func, from, to, val.1),
+        )
+    }
+
+    fn meet_edges(
+        &self,
+        func: &BBFunction,
+        to: usize,
+        preds: &[usize],
+        in_vals: &[Self::Val],
+    ) -> Self::Val {
+        let (a_vals, b_vals): (Vec<_>, Vec<_>) = in_vals.iter().cloned().unzip();
+        (
+            self.a.meet_edges(func, to, preds, &a_vals),
+            self.b.meet_edges(func, to, preds, &b_vals),
+        )
+    }
+
+    fn transfer(&self, block: &BasicBlock, in_val: &Self::Val) -> Self::Val {
+        (
+            self.a.transfer(block, &in_val.0),
+            self.b.transfer(block, &in_val.1),
+        )
+    }
+
+    fn transfer_instr(&self, block: &BasicBlock, k: usize, in_val: &Self::Val) -> Self::Val {
+        (
+            self.a.transfer_instr(block, k, &in_val.0),
+            self.b.transfer_instr(block, k, &in_val.1),
+        )
+    }
+
+    fn finish(&self, func: &BBFunction, exit_val: Self::Val) -> Self::Val {
+        (
+            self.a.finish(func, exit_val.0),
+            self.b.finish(func, exit_val.1),
+        )
+    }
+
+    fn widen(&self, old: &Self::Val, new: Self::Val) -> Self::Val {
+        (self.a.widen(&old.0, new.0), self.b.widen(&old.1, new.1))
+    }
+
+    /// Passes that never widen keep the default identity widening, so the
+    /// earlier of the two delays can be used for both
+    fn widen_delay(&self) -> usize {
+        self.a.widen_delay().min(self.b.widen_delay())
+    }
+}